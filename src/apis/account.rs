@@ -3,17 +3,48 @@
 //! `account` gives access to the Account API and the various endpoints associated with it.
 //! This allows you to obtain account information either by account UUID or in bulk (all accounts).
 
-use crate::constants::accounts::{LIST_ACCOUNT_MAXIMUM, RESOURCE_ENDPOINT};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+
+use crate::apis::OrderApi;
+use crate::constants::accounts::{LEDGER_ROOT_ENDPOINT, LIST_ACCOUNT_MAXIMUM, RESOURCE_ENDPOINT};
 use crate::errors::CbError;
 use crate::http_agent::SecureHttpAgent;
-use crate::models::account::{Account, AccountListQuery, AccountWrapper, PaginatedAccounts};
-use crate::traits::{HttpAgent, NoQuery};
+use crate::models::account::{
+    Account, AccountListQuery, AccountTransactionsQuery, AccountWrapper, Hold, HoldType,
+    LedgerEntry, LedgerWrapper, PaginatedAccounts,
+};
+use crate::models::order::{OrderListQuery, OrderSide, OrderStatus};
+use crate::models::product::ProductType;
+use crate::models::shared::Balance;
+#[cfg(feature = "websocket")]
+use crate::models::websocket::{Event, Message};
+use crate::time::Clock;
+use crate::traits::{HttpAgent, NoQuery, Paginate};
 use crate::types::CbResult;
 
+/// Short lived cache of accounts obtained by UUID.
+#[derive(Debug)]
+struct AccountCache {
+    /// Amount of time an account is served from the cache.
+    ttl: Duration,
+    /// Accounts by UUID along with when they were obtained.
+    accounts: HashMap<String, (Instant, Account)>,
+}
+
 /// Provides access to the Account API for the service.
+#[derive(Clone)]
 pub struct AccountApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
+    /// Cached accounts, shared between clones.
+    cache: Arc<Mutex<AccountCache>>,
+    /// Source of the current time.
+    clock: Arc<dyn Clock>,
 }
 
 impl AccountApi {
@@ -22,8 +53,98 @@ impl AccountApi {
     /// # Arguments
     ///
     /// * `signer` - A Signer that include the API Key & Secret along with a client to make requests.
-    pub(crate) fn new(agent: Option<SecureHttpAgent>) -> Self {
-        Self { agent }
+    /// * `cache_ttl` - Amount of time an account is served from the cache by `get_cached`.
+    /// * `clock` - Source of the current time.
+    pub(crate) fn new(
+        agent: Option<SecureHttpAgent>,
+        cache_ttl: Duration,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            agent,
+            cache: Arc::new(Mutex::new(AccountCache {
+                ttl: cache_ttl,
+                accounts: HashMap::new(),
+            })),
+            clock,
+        }
+    }
+
+    /// Obtains a single account based on the Account UUID, serving it from the cache if it was
+    /// obtained within the cache TTL. The TTL is configured with the `RestClientBuilder`.
+    ///
+    /// NOTE: Within the TTL the account is served from memory, only a cache miss makes a request
+    /// to the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_uuid` - A string the represents the account's UUID.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn get_cached(&mut self, account_uuid: &str) -> CbResult<Account> {
+        {
+            let cache = self.lock_cache();
+            if let Some((obtained, account)) = cache.accounts.get(account_uuid) {
+                if obtained.elapsed() < cache.ttl {
+                    return Ok(account.clone());
+                }
+            }
+        }
+
+        let account = self.get(account_uuid).await?;
+        self.lock_cache()
+            .accounts
+            .insert(account_uuid.to_string(), (Instant::now(), account.clone()));
+        Ok(account)
+    }
+
+    /// Removes an account from the cache, the next `get_cached` obtains it from the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_uuid` - A string the represents the account's UUID.
+    pub fn invalidate(&self, account_uuid: &str) {
+        self.lock_cache().accounts.remove(account_uuid);
+    }
+
+    /// Removes all accounts from the cache.
+    pub fn invalidate_all(&self) {
+        self.lock_cache().accounts.clear();
+    }
+
+    /// Invalidates the cache if the WebSocket message contains any user order updates, such as an
+    /// order being opened, filled, or cancelled, since holds and balances are likely to have
+    /// changed. Intended to be called from a WebSocket listener with a clone of the API, clones
+    /// share the same cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message received from the WebSocket.
+    #[cfg(feature = "websocket")]
+    pub fn invalidate_on_message(&self, message: &Message) {
+        let has_orders = message.events.iter().any(|event| match event {
+            Event::User(event) => !event.orders.is_empty(),
+            _ => false,
+        });
+
+        if has_orders {
+            self.invalidate_all();
+        }
+    }
+
+    /// Locks the cache, recovering it if a previous holder panicked.
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, AccountCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 
     /// Obtains a single account based on the Account UUID (ex. "XXXX-YYYY-ZZZZ"). This is the most
@@ -51,10 +172,7 @@ impl AccountApi {
         let agent = get_auth!(self.agent, "get account");
         let resource = format!("{RESOURCE_ENDPOINT}/{account_uuid}");
         let response = agent.get(&resource, &NoQuery).await?;
-        let data: AccountWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: AccountWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -127,26 +245,56 @@ impl AccountApi {
     /// * `CbError::BadStatus` - If the status code was not 200.
     /// * `CbError::BadJwt` - If there was an issue creating the JWT.
     pub async fn get_all(&mut self, query: &AccountListQuery) -> CbResult<Vec<Account>> {
+        self.get_all_cancellable(query, &CancellationToken::new())
+            .await
+    }
+
+    /// Obtains all accounts available to the API Key, stopping early if the token is cancelled.
+    /// The accounts obtained before the cancellation are returned, check `cancel.is_cancelled()`
+    /// to determine if the result is partial. A request in progress when the token is cancelled
+    /// is abandoned.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. `QoL` function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Parameters to control the query, such as limit.
+    /// * `cancel` - Token used to stop obtaining additional accounts.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn get_all_cancellable(
+        &mut self,
+        query: &AccountListQuery,
+        cancel: &CancellationToken,
+    ) -> CbResult<Vec<Account>> {
         is_auth!(self.agent, "get all accounts");
 
-        let mut query = query.clone().limit(LIST_ACCOUNT_MAXIMUM);
+        let query = query.clone().limit(LIST_ACCOUNT_MAXIMUM);
+        let mut pages = self.account_pages(&query);
         let mut all_accounts = Vec::new();
 
         loop {
             // Fetch accounts with the current query, propagating any errors.
-            let mut listed = self.get_bulk(&query).await?;
+            let mut accounts = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                accounts = pages.next_page() => match accounts {
+                    Some(accounts) => accounts?,
+                    None => break,
+                },
+            };
 
             // Append fetched accounts to the result list.
-            all_accounts.append(&mut listed.accounts);
-
-            // Check if there's more data to fetch.
-            if listed.has_next {
-                // Update the cursor for the next request.
-                query.cursor = Some(listed.cursor);
-            } else {
-                // No more data to fetch.
-                break;
-            }
+            all_accounts.append(&mut accounts);
         }
 
         Ok(all_accounts)
@@ -175,10 +323,169 @@ impl AccountApi {
     pub async fn get_bulk(&mut self, query: &AccountListQuery) -> CbResult<PaginatedAccounts> {
         let agent = get_auth!(self.agent, "get bulk accounts");
         let response = agent.get(RESOURCE_ENDPOINT, query).await?;
-        let data: PaginatedAccounts = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PaginatedAccounts = agent.parse_json(response).await?;
         Ok(data)
     }
+
+    /// Creates a paginator that obtains the accounts matching the query one page at a time,
+    /// following the cursor of each page. Use `Paginate::into_stream` to lazily obtain every
+    /// account. Errors from `get_bulk` are returned by `Paginate::next_page`.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. `QoL` function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Parameters to control the query, such as limit.
+    pub fn account_pages(&self, query: &AccountListQuery) -> AccountPaginator {
+        AccountPaginator {
+            api: self.clone(),
+            query: Some(query.clone()),
+        }
+    }
+
+    /// Obtains the transaction ledger for an account, such as deposits, withdrawals, trades,
+    /// and fees. Every page after `starting_after` in the query is obtained, newest first.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. `QoL` function that may require additional API requests than
+    /// normal. The ledger is served by the Coinbase App (v2) API, authenticated with the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_uuid` - A string the represents the account's UUID.
+    /// * `query` - Parameters to control the query, such as page size.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    ///
+    /// # Endpoint / Reference
+    ///
+    /// * <https://api.coinbase.com/v2/accounts/{account_uuid}/transactions>
+    /// * <https://docs.cdp.coinbase.com/coinbase-app/docs/api-transactions>
+    pub async fn account_transactions(
+        &mut self,
+        account_uuid: &str,
+        query: &AccountTransactionsQuery,
+    ) -> CbResult<Vec<LedgerEntry>> {
+        let agent = get_auth!(self.agent, "get account transactions");
+        let resource = format!("{LEDGER_ROOT_ENDPOINT}/{account_uuid}/transactions");
+
+        let mut query = query.clone();
+        let mut entries = Vec::new();
+
+        loop {
+            let response = agent.get(&resource, &query).await?;
+            let mut data: LedgerWrapper = agent.parse_json(response).await?;
+            entries.append(&mut data.data);
+
+            match data.pagination.next_starting_after {
+                Some(next) if !next.is_empty() => query.starting_after = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Obtains the itemized holds reducing the available balance of an account for open spot
+    /// orders, using the outstanding hold amount of each order. BUY orders hold the quote currency
+    /// and SELL orders hold the base currency.
+    ///
+    /// NOTE: The Advanced Trade API does not expose the holds of an account, only their total.
+    /// Funds held for any other reason, such as pending withdrawals or margin for futures, are not
+    /// reported here. Use `Account::unattributed_hold` to obtain their total.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. `QoL` function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_uuid` - A string the represents the account's UUID.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn open_order_holds(&mut self, account_uuid: &str) -> CbResult<Vec<Hold>> {
+        let account = self.get(account_uuid).await?;
+        if account.hold.value <= 0.0 {
+            return Ok(vec![]);
+        }
+
+        // Attribute the hold to the open spot orders spending the currency of the account.
+        let order_api = OrderApi::new(self.agent.clone(), Arc::clone(&self.clock));
+        let query = OrderListQuery::new()
+            .order_status(&[OrderStatus::Open])
+            .product_type(ProductType::Spot);
+        let mut pages = order_api.order_pages(&query);
+        let mut holds: Vec<Hold> = vec![];
+
+        while let Some(orders) = pages.next_page().await {
+            for order in orders? {
+                let Some((base, quote)) = order.product_id.split_once('-') else {
+                    continue;
+                };
+
+                let held_currency = match order.side {
+                    OrderSide::Buy => quote,
+                    OrderSide::Sell => base,
+                    OrderSide::Unknown => continue,
+                };
+
+                if order.outstanding_hold_amount > 0.0 && held_currency == account.currency {
+                    holds.push(Hold {
+                        amount: Balance::new(
+                            order.outstanding_hold_amount,
+                            account.currency.clone(),
+                        ),
+                        r#type: HoldType::Order,
+                        order_id: order.order_id,
+                        created_at: order.created_time,
+                    });
+                }
+            }
+        }
+
+        Ok(holds)
+    }
+}
+
+/// Paginator that obtains accounts one page at a time, created by `AccountApi::account_pages`.
+pub struct AccountPaginator {
+    /// API used to obtain the pages.
+    api: AccountApi,
+    /// Query for the next page, `None` once there are no additional pages.
+    query: Option<AccountListQuery>,
+}
+
+#[async_trait]
+impl Paginate for AccountPaginator {
+    type Item = Account;
+
+    async fn next_page(&mut self) -> Option<CbResult<Vec<Account>>> {
+        let mut query = self.query.take()?;
+        let page = match self.api.get_bulk(&query).await {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // Follow the cursor of this page if there are additional accounts.
+        if page.has_next {
+            query.cursor = Some(page.cursor);
+            self.query = Some(query);
+        }
+        Some(Ok(page.accounts))
+    }
 }
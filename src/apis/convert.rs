@@ -11,6 +11,7 @@ use crate::traits::{HttpAgent, NoQuery};
 use crate::types::CbResult;
 
 /// Provides access to the Convert API for the service.
+#[derive(Clone)]
 pub struct ConvertApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
@@ -54,10 +55,7 @@ impl ConvertApi {
     pub async fn create_quote(&mut self, request: &ConvertQuoteRequest) -> CbResult<Trade> {
         let agent = get_auth!(self.agent, "create convert quote");
         let response = agent.post(QUOTE_ENDPOINT, &NoQuery, request).await?;
-        let data = response
-            .json::<TradeWrapper>()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: TradeWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -86,10 +84,7 @@ impl ConvertApi {
         let agent = get_auth!(self.agent, "get convert trade");
         let resource = format!("{TRADE_ENDPOINT}/{trade_id}");
         let response = agent.get(&resource, query).await?;
-        let data: TradeWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: TradeWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -118,10 +113,7 @@ impl ConvertApi {
         let agent = get_auth!(self.agent, "commit convert quote");
         let resource = format!("{TRADE_ENDPOINT}/{trade_id}");
         let response = agent.post(&resource, &NoQuery, query).await?;
-        let data: TradeWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: TradeWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 }
@@ -2,14 +2,21 @@
 //!
 //! `data` gives access to the Data API and the various endpoints associated with it.
 
-use crate::constants::data::KEY_PERMISSIONS_ENDPOINT;
+use crate::constants::data::{
+    FUTURES_BALANCE_SUMMARY_ENDPOINT, FUTURES_SWEEPS_ENDPOINT, FUTURES_SWEEPS_SCHEDULE_ENDPOINT,
+    KEY_PERMISSIONS_ENDPOINT,
+};
 use crate::errors::CbError;
 use crate::http_agent::SecureHttpAgent;
-use crate::models::data::KeyPermissions;
+use crate::models::data::{
+    FuturesBalanceSummary, FuturesBalanceSummaryWrapper, FuturesSweep, FuturesSweepRequest,
+    FuturesSweepSuccessWrapper, FuturesSweepsWrapper, KeyPermissions,
+};
 use crate::traits::{HttpAgent, NoQuery};
 use crate::types::CbResult;
 
 /// Provides access to the Data API for the service.
+#[derive(Clone)]
 pub struct DataApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
@@ -44,10 +51,112 @@ impl DataApi {
     pub async fn key_permissions(&mut self) -> CbResult<KeyPermissions> {
         let agent = get_auth!(self.agent, "get key permissions");
         let response = agent.get(KEY_PERMISSIONS_ENDPOINT, &NoQuery).await?;
-        let data: KeyPermissions = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: KeyPermissions = agent.parse_json(response).await?;
         Ok(data)
     }
+
+    /// Get the futures (CFM) balance summary, containing collateral, buying power, and margin
+    /// information that is not available from the spot accounts.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    ///
+    /// # Endpoint / Reference
+    ///
+    /// * <https://api.coinbase.com/api/v3/brokerage/cfm/balance_summary>
+    /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_getfcmbalancesummary>
+    pub async fn futures_balance_summary(&mut self) -> CbResult<FuturesBalanceSummary> {
+        let agent = get_auth!(self.agent, "get futures balance summary");
+        let response = agent
+            .get(FUTURES_BALANCE_SUMMARY_ENDPOINT, &NoQuery)
+            .await?;
+        let data: FuturesBalanceSummaryWrapper = agent.parse_json(response).await?;
+        Ok(data.into())
+    }
+
+    /// Obtains the pending and processing sweeps of funds from the futures (CFM) account to the
+    /// spot account.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    ///
+    /// # Endpoint / Reference
+    ///
+    /// * <https://api.coinbase.com/api/v3/brokerage/cfm/sweeps>
+    /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_getfcmsweeps>
+    pub async fn list_futures_sweeps(&mut self) -> CbResult<Vec<FuturesSweep>> {
+        let agent = get_auth!(self.agent, "list futures sweeps");
+        let response = agent.get(FUTURES_SWEEPS_ENDPOINT, &NoQuery).await?;
+        let data: FuturesSweepsWrapper = agent.parse_json(response).await?;
+        Ok(data.into())
+    }
+
+    /// Schedules a sweep of USD funds from the futures (CFM) account to the spot account.
+    /// Only one sweep may be pending at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `usd_amount` - Amount of USD to sweep from the futures account.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::BadRequest` - If the amount is not greater than zero.
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    ///
+    /// # Endpoint / Reference
+    ///
+    /// * <https://api.coinbase.com/api/v3/brokerage/cfm/sweeps/schedule>
+    /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_schedulefcmsweep>
+    pub async fn schedule_futures_sweep(&mut self, usd_amount: f64) -> CbResult<bool> {
+        let agent = get_auth!(self.agent, "schedule futures sweep");
+        let request = FuturesSweepRequest::new(usd_amount);
+        let response = agent
+            .post(FUTURES_SWEEPS_SCHEDULE_ENDPOINT, &NoQuery, &request)
+            .await?;
+        let data: FuturesSweepSuccessWrapper = agent.parse_json(response).await?;
+        Ok(data.into())
+    }
+
+    /// Cancels the pending sweep of funds from the futures (CFM) account to the spot account.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    ///
+    /// # Endpoint / Reference
+    ///
+    /// * <https://api.coinbase.com/api/v3/brokerage/cfm/sweeps>
+    /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_cancelfcmsweep>
+    pub async fn cancel_pending_futures_sweep(&mut self) -> CbResult<bool> {
+        let agent = get_auth!(self.agent, "cancel pending futures sweep");
+        let response = agent.delete(FUTURES_SWEEPS_ENDPOINT, &NoQuery).await?;
+        let data: FuturesSweepSuccessWrapper = agent.parse_json(response).await?;
+        Ok(data.into())
+    }
 }
@@ -11,6 +11,7 @@ use crate::traits::HttpAgent;
 use crate::types::CbResult;
 
 /// Provides access to the Fee API for the service.
+#[derive(Clone)]
 pub struct FeeApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
@@ -52,10 +53,7 @@ impl FeeApi {
     ) -> CbResult<TransactionSummary> {
         let agent = get_auth!(self.agent, "get fee transaction summary");
         let response = agent.get(RESOURCE_ENDPOINT, query).await?;
-        let data: TransactionSummary = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: TransactionSummary = agent.parse_json(response).await?;
         Ok(data)
     }
 }
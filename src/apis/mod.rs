@@ -2,22 +2,38 @@
 //!
 //! This module contains all the API Endpoints that are used to interact with Coinbase Advanced.
 
+#[cfg(feature = "trade")]
 mod account;
+#[cfg(feature = "trade")]
 mod convert;
+#[cfg(feature = "trade")]
 mod data;
+#[cfg(feature = "trade")]
 mod fee;
+#[cfg(feature = "trade")]
 mod order;
+#[cfg(feature = "trade")]
 mod payment;
+#[cfg(feature = "trade")]
 mod portfolio;
+#[cfg(feature = "trade")]
 mod product;
 mod public;
 
-pub use account::AccountApi;
+#[cfg(feature = "trade")]
+pub use account::{AccountApi, AccountPaginator};
+#[cfg(feature = "trade")]
 pub use convert::ConvertApi;
+#[cfg(feature = "trade")]
 pub use data::DataApi;
+#[cfg(feature = "trade")]
 pub use fee::FeeApi;
-pub use order::OrderApi;
+#[cfg(feature = "trade")]
+pub use order::{FillPaginator, OrderApi, OrderPaginator};
+#[cfg(feature = "trade")]
 pub use payment::PaymentApi;
+#[cfg(feature = "trade")]
 pub use portfolio::PortfolioApi;
-pub use product::ProductApi;
+#[cfg(feature = "trade")]
+pub use product::{ProductApi, ProductPaginator};
 pub use public::PublicApi;
@@ -3,25 +3,47 @@
 //! `order` gives access to the Order API and the various endpoints associated with it.
 //! These allow you to obtain past created orders, create new orders, and cancel orders.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::apis::{AccountApi, PortfolioApi};
 use crate::constants::orders::{
-    BATCH_ENDPOINT, CANCEL_BATCH_ENDPOINT, CLOSE_POSITION_ENDPOINT, CREATE_PREVIEW_ENDPOINT,
-    EDIT_ENDPOINT, EDIT_PREVIEW_ENDPOINT, FILLS_ENDPOINT, RESOURCE_ENDPOINT,
+    BATCH_ENDPOINT, CANCEL_BATCH_ENDPOINT, CANCEL_BATCH_MAXIMUM, CLOSE_POSITION_ENDPOINT,
+    CREATE_PREVIEW_ENDPOINT, EDIT_ENDPOINT, EDIT_PREVIEW_ENDPOINT, FILLS_ENDPOINT,
+    RESOURCE_ENDPOINT,
 };
 use crate::errors::CbError;
 use crate::http_agent::SecureHttpAgent;
+use crate::models::account::AccountListQuery;
 use crate::models::order::{
-    Order, OrderCancelRequest, OrderCancelResponse, OrderCancelWrapper, OrderClosePositionRequest,
-    OrderCreatePreview, OrderCreateRequest, OrderCreateResponse, OrderEditPreview,
-    OrderEditRequest, OrderEditResponse, OrderListFillsQuery, OrderListQuery, OrderStatus,
-    OrderWrapper, PaginatedFills, PaginatedOrders,
+    Fill, Order, OrderCancelRequest, OrderCancelResponse, OrderCancelWrapper,
+    OrderClosePositionRequest, OrderCreateBuilder, OrderCreatePreview, OrderCreateRequest,
+    OrderCreateResponse, OrderEditPreview, OrderEditRequest, OrderEditResponse,
+    OrderListFillsQuery, OrderListQuery, OrderSide, OrderStatus, OrderType, OrderWrapper,
+    PaginatedFills, PaginatedOrders, TimeInForce,
+};
+use crate::models::portfolio::{
+    PortfolioBreakdown, PortfolioBreakdownQuery, PortfolioListQuery, PositionSide,
 };
-use crate::traits::{HttpAgent, NoQuery};
+use crate::time::{self, Clock};
+use crate::traits::{HttpAgent, NoQuery, Paginate};
 use crate::types::CbResult;
+use crate::utils::write_lines;
 
 /// Provides access to the Order API for the service.
+#[derive(Clone)]
 pub struct OrderApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
+    /// Source of the current time.
+    clock: Arc<dyn Clock>,
 }
 
 impl OrderApi {
@@ -30,8 +52,9 @@ impl OrderApi {
     /// # Arguments
     ///
     /// * `agent` - A agent that include the API Key & Secret along with a client to make requests.
-    pub(crate) fn new(agent: Option<SecureHttpAgent>) -> Self {
-        Self { agent }
+    /// * `clock` - Source of the current time.
+    pub(crate) fn new(agent: Option<SecureHttpAgent>, clock: Arc<dyn Clock>) -> Self {
+        Self { agent, clock }
     }
 
     /// Cancel orders.
@@ -60,15 +83,15 @@ impl OrderApi {
     ) -> CbResult<Vec<OrderCancelResponse>> {
         let agent = get_auth!(self.agent, "cancel orders");
         let response = agent.post(CANCEL_BATCH_ENDPOINT, &NoQuery, request).await?;
-        let data: OrderCancelWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderCancelWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
     /// Cancel all OPEN orders for a specific product ID.
     ///
+    /// Orders are cancelled in batches that respect the maximum amount of order IDs allowed per
+    /// cancel request, the responses from every batch are combined.
+    ///
     /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
     /// than normal.
     ///
@@ -98,20 +121,96 @@ impl OrderApi {
         let open_orders = self.get_all(product_id, &query).await?;
 
         // Collect the IDs of orders to cancel.
-        let request = OrderCancelRequest::new(
-            &open_orders
-                .iter()
-                .map(|order| order.order_id.clone())
-                .collect::<Vec<String>>(),
-        );
-
-        // No orders to cancel.
-        if request.order_ids.is_empty() {
-            return Ok(vec![]);
+        let order_ids: Vec<String> = open_orders
+            .into_iter()
+            .map(|order| order.order_id)
+            .collect();
+
+        self.cancel_in_batches(&order_ids).await
+    }
+
+    /// Cancel OPEN orders for a specific product ID that were created more than `age` ago.
+    /// Orders with a `created_time` that cannot be parsed are not cancelled.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to cancel stale OPEN orders for.
+    /// * `age` - Minimum age of an order for it to be cancelled.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn cancel_older_than(
+        &mut self,
+        product_id: &str,
+        age: Duration,
+    ) -> CbResult<Vec<OrderCancelResponse>> {
+        is_auth!(self.agent, "cancel orders older than");
+
+        let query = OrderListQuery {
+            product_ids: Some(vec![product_id.to_string()]),
+            order_status: Some(vec![OrderStatus::Open]),
+            ..Default::default()
+        };
+
+        // Obtain all open orders for the given product.
+        let open_orders = self.get_all(product_id, &query).await?;
+
+        // Collect the IDs of orders created before the cutoff.
+        let cutoff = self.clock.now().saturating_sub(age.as_secs());
+        let order_ids: Vec<String> = open_orders
+            .into_iter()
+            .filter(|order| {
+                time::from_rfc3339(&order.created_time).is_ok_and(|created| created < cutoff)
+            })
+            .map(|order| order.order_id)
+            .collect();
+
+        self.cancel_in_batches(&order_ids).await
+    }
+
+    /// Cancel all OPEN orders across every product.
+    ///
+    /// Open orders are listed without a product filter and cancelled in batches that respect
+    /// the maximum amount of order IDs allowed per cancel request.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn cancel_all_orders(&mut self) -> CbResult<Vec<OrderCancelResponse>> {
+        is_auth!(self.agent, "cancel all orders");
+
+        let query = OrderListQuery {
+            order_status: Some(vec![OrderStatus::Open]),
+            ..Default::default()
+        };
+
+        // Obtain all open orders, regardless of product.
+        let mut order_ids: Vec<String> = vec![];
+        let mut pages = self.order_pages(&query);
+        while let Some(orders) = pages.next_page().await {
+            order_ids.extend(orders?.into_iter().map(|order| order.order_id));
         }
 
-        // Cancel the orders and return the response.
-        self.cancel(&request).await
+        self.cancel_in_batches(&order_ids).await
     }
 
     /// Edit an order with a specified new size, or new price. Only limit order types, with time
@@ -140,14 +239,12 @@ impl OrderApi {
     pub async fn edit(&mut self, request: &OrderEditRequest) -> CbResult<OrderEditResponse> {
         let agent = get_auth!(self.agent, "edit order");
         let response = agent.post(EDIT_ENDPOINT, &NoQuery, request).await?;
-        let data: OrderEditResponse = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderEditResponse = agent.parse_json(response).await?;
         Ok(data)
     }
 
-    /// Preview creating an order.
+    /// Preview creating an order. The `preview_id` of the preview can be provided when creating
+    /// the order to link the two.
     ///
     /// # Arguments
     ///
@@ -175,10 +272,7 @@ impl OrderApi {
         let response = agent
             .post(CREATE_PREVIEW_ENDPOINT, &NoQuery, request)
             .await?;
-        let data: OrderCreatePreview = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderCreatePreview = agent.parse_json(response).await?;
         Ok(data)
     }
 
@@ -206,10 +300,7 @@ impl OrderApi {
     pub async fn preview_edit(&mut self, request: &OrderEditRequest) -> CbResult<OrderEditPreview> {
         let agent = get_auth!(self.agent, "preview edit order");
         let response = agent.post(EDIT_PREVIEW_ENDPOINT, &NoQuery, request).await?;
-        let data: OrderEditPreview = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderEditPreview = agent.parse_json(response).await?;
         Ok(data)
     }
 
@@ -236,13 +327,245 @@ impl OrderApi {
     pub async fn create(&mut self, request: &OrderCreateRequest) -> CbResult<OrderCreateResponse> {
         let agent = get_auth!(self.agent, "create order");
         let response = agent.post(RESOURCE_ENDPOINT, &NoQuery, request).await?;
-        let data: OrderCreateResponse = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderCreateResponse = agent.parse_json(response).await?;
         Ok(data)
     }
 
+    /// Create a reduce-only order after checking it against the open position for the product.
+    /// The order is rejected if there is no open position, if it is on the same side as the
+    /// position, or if its base size is larger than the position.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - A reduce-only order with a base size.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::BadRequest` - If the order would not only reduce the open position.
+    pub async fn create_reduce_only(
+        &mut self,
+        request: &OrderCreateRequest,
+    ) -> CbResult<OrderCreateResponse> {
+        is_auth!(self.agent, "create reduce only order");
+
+        let product_id = &request.product_id;
+        if request.reduce_only != Some(true) {
+            return Err(CbError::BadRequest(format!(
+                "order for {product_id} is not reduce only"
+            )));
+        }
+
+        let Some(order_size) = request.order_configuration.base_size() else {
+            return Err(CbError::BadRequest(format!(
+                "reduce only order for {product_id} requires a base size"
+            )));
+        };
+
+        let Some((side, position_size)) = self.find_position(product_id).await? else {
+            return Err(CbError::BadRequest(format!(
+                "reduce only order requires an open position for {product_id}"
+            )));
+        };
+
+        if side != request.side {
+            return Err(CbError::BadRequest(format!(
+                "reduce only order would increase the open position for {product_id}"
+            )));
+        } else if order_size > position_size {
+            return Err(CbError::BadRequest(format!(
+                "reduce only order of {order_size} exceeds the position of {position_size} for {product_id}"
+            )));
+        }
+
+        self.create(request).await
+    }
+
+    /// Create a market order that buys the product, spending the amount of quote currency
+    /// provided. Convenience wrapper around `OrderCreateBuilder` and `create`.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to buy, such as BTC-USD.
+    /// * `quote_size` - Amount of quote currency to spend.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::BadParse` - If the order could not be built from the arguments.
+    pub async fn market_buy(
+        &mut self,
+        product_id: &str,
+        quote_size: f64,
+    ) -> CbResult<OrderCreateResponse> {
+        let request = OrderCreateBuilder::new(product_id, OrderSide::Buy)
+            .order_type(OrderType::Market)
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .quote_size(quote_size)
+            .build()?;
+        self.create(&request).await
+    }
+
+    /// Create a market order that sells the amount of the base currency provided. Convenience
+    /// wrapper around `OrderCreateBuilder` and `create`.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to sell, such as BTC-USD.
+    /// * `base_size` - Amount of base currency to sell.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::BadParse` - If the order could not be built from the arguments.
+    pub async fn market_sell(
+        &mut self,
+        product_id: &str,
+        base_size: f64,
+    ) -> CbResult<OrderCreateResponse> {
+        let request = OrderCreateBuilder::new(product_id, OrderSide::Sell)
+            .order_type(OrderType::Market)
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .base_size(base_size)
+            .build()?;
+        self.create(&request).await
+    }
+
+    /// Create a limit order that remains open until it is filled or cancelled. Convenience
+    /// wrapper around `OrderCreateBuilder` and `create`.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to trade, such as BTC-USD.
+    /// * `side` - Side of the order, either BUY or SELL.
+    /// * `base_size` - Amount of base currency to buy or sell.
+    /// * `limit_price` - Price the order is placed at, in quote currency.
+    /// * `post_only` - If the order should only make liquidity.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::BadParse` - If the order could not be built from the arguments.
+    pub async fn limit_gtc(
+        &mut self,
+        product_id: &str,
+        side: OrderSide,
+        base_size: f64,
+        limit_price: f64,
+        post_only: bool,
+    ) -> CbResult<OrderCreateResponse> {
+        let request = OrderCreateBuilder::new(product_id, side)
+            .order_type(OrderType::Limit)
+            .time_in_force(TimeInForce::GoodUntilCancelled)
+            .base_size(base_size)
+            .limit_price(limit_price)
+            .post_only(post_only)
+            .build()?;
+        self.create(&request).await
+    }
+
+    /// Create an order after verifying that the account funding it has sufficient available
+    /// balance. The order is previewed first to obtain its total including estimated fees, BUY
+    /// orders are checked against the quote currency account and SELL orders against the base
+    /// currency account. Intended for spot products in the `BASE-QUOTE` format. The order is
+    /// linked to the preview unless a `preview_id` was already provided.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - A struct containing the order details to create.
+    /// * `account_api` - Account API used to obtain the balance of the funding account.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::NotFound` - If the funding account was not found.
+    /// * `CbError::BadRequest` - If the available balance is insufficient or the product ID is
+    ///   not in the `BASE-QUOTE` format.
+    pub async fn create_with_balance_check(
+        &mut self,
+        request: &OrderCreateRequest,
+        account_api: &mut AccountApi,
+    ) -> CbResult<OrderCreateResponse> {
+        is_auth!(self.agent, "create order with balance check");
+
+        let (base, quote) = request.product_id.split_once('-').ok_or_else(|| {
+            CbError::BadRequest(format!(
+                "product ID '{}' is not in the BASE-QUOTE format",
+                request.product_id
+            ))
+        })?;
+
+        // Preview the order to obtain the amount required, including fees.
+        let mut preview_request = request.clone();
+        preview_request.client_order_id = String::new();
+        preview_request.is_preview = true;
+        preview_request.preview_id = None;
+        let preview = self.preview_create(&preview_request).await?;
+
+        let (currency, required) = match request.side {
+            OrderSide::Buy => (quote, preview.order_total),
+            OrderSide::Sell => (base, preview.base_size),
+            OrderSide::Unknown => {
+                return Err(CbError::BadRequest(
+                    "order side must be BUY or SELL".to_string(),
+                ))
+            }
+        };
+
+        let account = account_api
+            .get_by_id(currency, &AccountListQuery::new())
+            .await?;
+        let available = account.available_balance.value;
+        if available < required {
+            return Err(CbError::BadRequest(format!(
+                "insufficient {currency} balance: {available} available, {required} required"
+            )));
+        }
+
+        // Link the order to the preview that was checked.
+        let mut request = request.clone();
+        if request.preview_id.is_none() && !preview.preview_id.is_empty() {
+            request.preview_id = Some(preview.preview_id);
+        }
+        self.create(&request).await
+    }
+
     /// Obtains a single order based on the Order ID (ex. "XXXX-YYYY-ZZZZ").
     ///
     /// # Arguments
@@ -267,10 +590,7 @@ impl OrderApi {
         let agent = get_auth!(self.agent, "get order");
         let resource = format!("{RESOURCE_ENDPOINT}/historical/{order_id}");
         let response = agent.get(&resource, &NoQuery).await?;
-        let data: OrderWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -297,17 +617,35 @@ impl OrderApi {
     pub async fn get_bulk(&mut self, query: &OrderListQuery) -> CbResult<PaginatedOrders> {
         let agent = get_auth!(self.agent, "get bulk orders");
         let response = agent.get(BATCH_ENDPOINT, query).await?;
-        let data: PaginatedOrders = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PaginatedOrders = agent.parse_json(response).await?;
         Ok(data)
     }
 
+    /// Creates a paginator that obtains the orders matching the query one page at a time,
+    /// following the cursor of each page. Use `Paginate::into_stream` to lazily obtain every
+    /// order. Errors from `get_bulk` are returned by `Paginate::next_page`.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A Parameters to modify what is returned by the API.
+    pub fn order_pages(&self, query: &OrderListQuery) -> OrderPaginator {
+        OrderPaginator {
+            api: self.clone(),
+            query: Some(query.clone()),
+        }
+    }
+
     /// Obtains all orders for a product based on the product ID. (ex. "BTC-USD").
     /// This wraps `get_bulk` and makes several additional requests until there are no
     /// additional orders.
     ///
+    /// Results are deduplicated by order ID since unstable pagination, such as when using
+    /// `sort_by`, can otherwise repeat orders across pages. The order returned by the API is
+    /// preserved, use `sort_by_created_time` to sort the orders with the newest first.
+    ///
     /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than normal.
     ///
     /// # Arguments
@@ -328,28 +666,106 @@ impl OrderApi {
         &mut self,
         product_id: &str,
         query: &OrderListQuery,
+    ) -> CbResult<Vec<Order>> {
+        self.get_all_cancellable(product_id, query, &CancellationToken::new())
+            .await
+    }
+
+    /// Obtains all orders for a product, stopping early if the token is cancelled. The orders
+    /// obtained before the cancellation are returned, check `cancel.is_cancelled()` to determine
+    /// if the result is partial. A request in progress when the token is cancelled is abandoned.
+    ///
+    /// Results are deduplicated by order ID since unstable pagination, such as when using
+    /// `sort_by`, can otherwise repeat orders across pages. The order returned by the API is
+    /// preserved, use `sort_by_created_time` to sort the orders with the newest first.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Identifier for the account, such as BTC-USD or ETH-USD.
+    /// * `query` - A Parameters to modify what is returned by the API.
+    /// * `cancel` - Token used to stop obtaining additional orders.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn get_all_cancellable(
+        &mut self,
+        product_id: &str,
+        query: &OrderListQuery,
+        cancel: &CancellationToken,
     ) -> CbResult<Vec<Order>> {
         is_auth!(self.agent, "get all orders");
 
         // Set the product ID for the query.
-        let mut query = query.clone().product_ids(&[product_id.to_string()]);
+        let query = query.clone().product_ids(&[product_id.to_string()]);
         let mut all_orders: Vec<Order> = vec![];
+        let mut seen: HashSet<String> = HashSet::new();
 
-        // Fetch orders until no more pages are available.
+        // Fetch orders until no more pages are available, skipping repeated orders.
+        let mut pages = self.order_pages(&query);
         loop {
-            let listed_orders = self.get_bulk(&query).await?;
-            all_orders.extend(listed_orders.orders);
-
-            if listed_orders.has_next {
-                query.cursor = Some(listed_orders.cursor);
-            } else {
-                break;
-            }
+            let orders = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                orders = pages.next_page() => match orders {
+                    Some(orders) => orders?,
+                    None => break,
+                },
+            };
+            all_orders.extend(
+                orders
+                    .into_iter()
+                    .filter(|order| seen.insert(order.order_id.clone())),
+            );
         }
 
         Ok(all_orders)
     }
 
+    /// Lazily obtains the orders for a product created within a span of time, one page at a time.
+    /// The cursor of each page is followed until there are no additional orders, allowing large
+    /// histories to be processed without holding every order in memory. The stream ends after
+    /// the first error is yielded.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Identifier for the product, such as BTC-USD or ETH-USD.
+    /// * `start_unix` - Start of the span in UNIX format (seconds), inclusive.
+    /// * `end_unix` - End of the span in UNIX format (seconds), exclusive.
+    ///
+    /// # Errors
+    ///
+    /// Yields a `CbError::BadParse` if the timestamps cannot be formatted, otherwise yields the
+    /// errors of `get_bulk` if a page could not be obtained.
+    pub fn orders_in_range(
+        &self,
+        product_id: &str,
+        start_unix: u64,
+        end_unix: u64,
+    ) -> impl Stream<Item = CbResult<Order>> {
+        let query = time::to_rfc3339(start_unix).and_then(|start| {
+            Ok(OrderListQuery::new()
+                .product_ids(&[product_id.to_string()])
+                .start_date(start)
+                .end_date(time::to_rfc3339(end_unix)?))
+        });
+
+        match query {
+            Ok(query) => self.order_pages(&query).into_stream().left_stream(),
+            Err(err) => stream::once(future::ready(Err(err))).right_stream(),
+        }
+    }
+
     /// Obtains fills from the API.
     ///
     /// # Arguments
@@ -373,13 +789,169 @@ impl OrderApi {
     pub async fn fills(&mut self, query: &OrderListFillsQuery) -> CbResult<PaginatedFills> {
         let agent = get_auth!(self.agent, "get fills");
         let response = agent.get(FILLS_ENDPOINT, query).await?;
-        let data: PaginatedFills = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PaginatedFills = agent.parse_json(response).await?;
         Ok(data)
     }
 
+    /// Creates a paginator that obtains the fills matching the query one page at a time,
+    /// following the cursor of each page. Use `Paginate::into_stream` to lazily obtain every
+    /// fill. Errors from `fills` are returned by `Paginate::next_page`.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A Parameters to modify what is returned by the API.
+    pub fn fill_pages(&self, query: &OrderListFillsQuery) -> FillPaginator {
+        FillPaginator {
+            api: self.clone(),
+            query: Some(query.clone()),
+        }
+    }
+
+    /// Obtains all fills for the given orders, grouped by order ID. This wraps `fills` and makes
+    /// several additional requests until there are no additional fills. Every requested order ID
+    /// is present in the result, with an empty vector if it has no fills.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_ids` - IDs of the orders to obtain fills for.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn fills_by_order(
+        &mut self,
+        order_ids: &[String],
+    ) -> CbResult<HashMap<String, Vec<Fill>>> {
+        is_auth!(self.agent, "get fills by order");
+
+        let mut grouped: HashMap<String, Vec<Fill>> = order_ids
+            .iter()
+            .map(|order_id| (order_id.clone(), vec![]))
+            .collect();
+
+        // No orders to obtain fills for.
+        if order_ids.is_empty() {
+            return Ok(grouped);
+        }
+
+        // Fetch fills until no more pages are available.
+        let query = OrderListFillsQuery::new().order_ids(order_ids);
+        let mut pages = self.fill_pages(&query);
+        while let Some(fills) = pages.next_page().await {
+            for fill in fills? {
+                grouped.entry(fill.order_id.clone()).or_default().push(fill);
+            }
+        }
+
+        Ok(grouped)
+    }
+
+    /// Obtains every fill matching the query, writing them to the writer as newline-delimited
+    /// JSON as each page arrives instead of holding them in memory. The cursor of each page is
+    /// followed until there are no additional fills. Returns the amount of fills written.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A Parameters to modify what is returned by the API.
+    /// * `writer` - Destination of the fills, such as a file or socket.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request or a fill.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::WriteError` - If there was an issue writing to the writer.
+    pub async fn fills_to_writer<W>(
+        &mut self,
+        query: &OrderListFillsQuery,
+        mut writer: W,
+    ) -> CbResult<usize>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        is_auth!(self.agent, "write fills");
+
+        let mut pages = self.fill_pages(query);
+        let mut written = 0;
+        while let Some(fills) = pages.next_page().await {
+            let fills = fills?;
+            write_lines(&mut writer, &fills).await?;
+            written += fills.len();
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| CbError::WriteError(e.to_string()))?;
+        Ok(written)
+    }
+
+    /// Obtains every order matching the query, writing them to the writer as newline-delimited
+    /// JSON as each page arrives instead of holding them in memory. The cursor of each page is
+    /// followed until there are no additional orders. Returns the amount of orders written.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A Parameters to modify what is returned by the API.
+    /// * `writer` - Destination of the orders, such as a file or socket.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request or an order.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::WriteError` - If there was an issue writing to the writer.
+    pub async fn orders_to_writer<W>(
+        &mut self,
+        query: &OrderListQuery,
+        mut writer: W,
+    ) -> CbResult<usize>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        is_auth!(self.agent, "write orders");
+
+        let mut pages = self.order_pages(query);
+        let mut written = 0;
+        while let Some(orders) = pages.next_page().await {
+            let orders = orders?;
+            write_lines(&mut writer, &orders).await?;
+            written += orders.len();
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| CbError::WriteError(e.to_string()))?;
+        Ok(written)
+    }
+
     /// Places an order to close any open positions for a specified `product_id`.
     ///
     /// # Arguments
@@ -408,10 +980,168 @@ impl OrderApi {
         let response = agent
             .post(CLOSE_POSITION_ENDPOINT, &NoQuery, request)
             .await?;
-        let data: OrderCreateResponse = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: OrderCreateResponse = agent.parse_json(response).await?;
         Ok(data)
     }
+
+    /// Closes the open perpetual or futures position for a product with a reduce-only market
+    /// order. Every portfolio is searched for the position, including the perpetuals (INTX)
+    /// portfolio. A long position is sold and a short position is bought for the full size of
+    /// the position.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests
+    /// than normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID of the position to close.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::NotFound` - If there is no open position for the product in any portfolio.
+    pub async fn flatten(&mut self, product_id: &str) -> CbResult<OrderCreateResponse> {
+        is_auth!(self.agent, "flatten position");
+
+        let Some((side, amount)) = self.find_position(product_id).await? else {
+            return Err(CbError::NotFound(format!("open position for {product_id}")));
+        };
+
+        let request = OrderCreateBuilder::new(product_id, side)
+            .order_type(OrderType::Market)
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .base_size(amount)
+            .reduce_only(true)
+            .build()?;
+        self.create(&request).await
+    }
+
+    /// Side and size of the order that closes the open position for a product, searching every
+    /// portfolio since positions are held in different portfolios, such as perpetuals in INTX.
+    async fn find_position(&self, product_id: &str) -> CbResult<Option<(OrderSide, f64)>> {
+        let mut portfolio_api = PortfolioApi::new(self.agent.clone());
+        let portfolios = portfolio_api.get_all(&PortfolioListQuery::new()).await?;
+
+        for portfolio in portfolios.iter().filter(|portfolio| !portfolio.deleted) {
+            let breakdown = portfolio_api
+                .get(&portfolio.uuid, &PortfolioBreakdownQuery::new())
+                .await?;
+
+            if let Some(position) = open_position(&breakdown, product_id) {
+                return Ok(Some(position));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Cancels the orders in batches that respect the maximum amount of order IDs allowed per
+    /// cancel request, collecting all of the responses.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_ids` - IDs of the orders to cancel.
+    async fn cancel_in_batches(
+        &mut self,
+        order_ids: &[String],
+    ) -> CbResult<Vec<OrderCancelResponse>> {
+        let mut responses: Vec<OrderCancelResponse> = vec![];
+        for chunk in order_ids.chunks(CANCEL_BATCH_MAXIMUM) {
+            let request = OrderCancelRequest::new(chunk);
+            responses.extend(self.cancel(&request).await?);
+        }
+
+        Ok(responses)
+    }
+}
+
+/// Side and size of the order that closes the open position for a product within the breakdown.
+/// Perpetual positions are checked before futures positions, flat positions are ignored.
+fn open_position(breakdown: &PortfolioBreakdown, product_id: &str) -> Option<(OrderSide, f64)> {
+    let perp = breakdown
+        .perp_positions
+        .iter()
+        .find(|position| position.product_id == product_id && position.net_size != 0.0)
+        .map(|position| {
+            let side = if position.net_size < 0.0 {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            (side, position.net_size.abs())
+        });
+
+    perp.or_else(|| {
+        breakdown
+            .futures_positions
+            .iter()
+            .filter(|position| position.product_id == product_id && position.amount != 0.0)
+            .find_map(|position| match position.side {
+                PositionSide::Long => Some((OrderSide::Sell, position.amount.abs())),
+                PositionSide::Short => Some((OrderSide::Buy, position.amount.abs())),
+                PositionSide::Unspecified => None,
+            })
+    })
+}
+
+/// Paginator that obtains orders one page at a time, created by `OrderApi::order_pages`.
+pub struct OrderPaginator {
+    /// API used to obtain the pages.
+    api: OrderApi,
+    /// Query for the next page, `None` once there are no additional pages.
+    query: Option<OrderListQuery>,
+}
+
+#[async_trait]
+impl Paginate for OrderPaginator {
+    type Item = Order;
+
+    async fn next_page(&mut self) -> Option<CbResult<Vec<Order>>> {
+        let mut query = self.query.take()?;
+        let page = match self.api.get_bulk(&query).await {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // Follow the cursor of this page if there are additional orders.
+        if page.has_next {
+            query.cursor = Some(page.cursor);
+            self.query = Some(query);
+        }
+        Some(Ok(page.orders))
+    }
+}
+
+/// Paginator that obtains fills one page at a time, created by `OrderApi::fill_pages`.
+pub struct FillPaginator {
+    /// API used to obtain the pages.
+    api: OrderApi,
+    /// Query for the next page, `None` once there are no additional pages.
+    query: Option<OrderListFillsQuery>,
+}
+
+#[async_trait]
+impl Paginate for FillPaginator {
+    type Item = Fill;
+
+    async fn next_page(&mut self) -> Option<CbResult<Vec<Fill>>> {
+        let mut query = self.query.take()?;
+        let page = match self.api.fills(&query).await {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // An empty cursor indicates there are no additional fills.
+        if !page.cursor.is_empty() {
+            query.cursor = Some(page.cursor);
+            self.query = Some(query);
+        }
+        Some(Ok(page.orders))
+    }
 }
@@ -10,6 +10,7 @@ use crate::traits::{HttpAgent, NoQuery};
 use crate::types::CbResult;
 
 /// Provides access to the Payment API for the service.
+#[derive(Clone)]
 pub struct PaymentApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
@@ -44,10 +45,7 @@ impl PaymentApi {
     pub async fn get_all(&mut self) -> CbResult<Vec<PaymentMethod>> {
         let agent = get_auth!(self.agent, "get all payment methods");
         let response = agent.get(RESOURCE_ENDPOINT, &NoQuery).await?;
-        let data: PaymentMethodsWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PaymentMethodsWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -75,10 +73,7 @@ impl PaymentApi {
         let agent = get_auth!(self.agent, "get payment method");
         let resource = format!("{RESOURCE_ENDPOINT}/{payment_method_id}");
         let response = agent.get(&resource, &NoQuery).await?;
-        let data: PaymentMethodWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PaymentMethodWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 }
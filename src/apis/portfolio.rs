@@ -8,13 +8,14 @@ use crate::errors::CbError;
 use crate::http_agent::SecureHttpAgent;
 use crate::models::portfolio::{
     Portfolio, PortfolioBreakdown, PortfolioBreakdownQuery, PortfolioBreakdownWrapper,
-    PortfolioListQuery, PortfolioModifyRequest, PortfolioMoveFundsRequest, PortfolioWrapper,
-    PortfoliosWrapper,
+    PortfolioListQuery, PortfolioModifyRequest, PortfolioMoveFundsRequest, PortfolioType,
+    PortfolioWrapper, PortfoliosWrapper,
 };
 use crate::traits::{HttpAgent, NoQuery};
 use crate::types::CbResult;
 
 /// Provides access to the Portfolio API for the service.
+#[derive(Clone)]
 pub struct PortfolioApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
@@ -53,13 +54,35 @@ impl PortfolioApi {
     pub async fn get_all(&mut self, query: &PortfolioListQuery) -> CbResult<Vec<Portfolio>> {
         let agent = get_auth!(self.agent, "get all portfolios");
         let response = agent.get(RESOURCE_ENDPOINT, query).await?;
-        let data: PortfoliosWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PortfoliosWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
+    /// Obtains the default portfolio of the user. Its UUID is used by operations that target a
+    /// specific portfolio, such as moving funds.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::NotFound` - If there is no default portfolio.
+    pub async fn default_portfolio(&mut self) -> CbResult<Portfolio> {
+        let query = PortfolioListQuery::new().portfolio_type(PortfolioType::Default);
+        self.get_all(&query)
+            .await?
+            .into_iter()
+            .find(|portfolio| portfolio.r#type == PortfolioType::Default && !portfolio.deleted)
+            .ok_or_else(|| CbError::NotFound("default portfolio".to_string()))
+    }
+
     /// Creates a new portfolio.
     ///
     /// # Arguments
@@ -83,10 +106,7 @@ impl PortfolioApi {
     pub async fn create(&mut self, request: &PortfolioModifyRequest) -> CbResult<Portfolio> {
         let agent = get_auth!(self.agent, "create portfolio");
         let response = agent.post(RESOURCE_ENDPOINT, &NoQuery, request).await?;
-        let data: PortfolioWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PortfolioWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -119,10 +139,7 @@ impl PortfolioApi {
         let agent = get_auth!(self.agent, "edit portfolio");
         let resource = format!("{RESOURCE_ENDPOINT}/{portfolio_uuid}");
         let response = agent.put(&resource, &NoQuery, request).await?;
-        let data: PortfolioWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PortfolioWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -206,10 +223,7 @@ impl PortfolioApi {
         let agent = get_auth!(self.agent, "get portfolio breakdown");
         let resource = format!("{RESOURCE_ENDPOINT}/{portfolio_uuid}");
         let response = agent.get(&resource, query).await?;
-        let data: PortfolioBreakdownWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: PortfolioBreakdownWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 }
@@ -4,24 +4,36 @@
 //! This allows you to obtain product information such as: Ticker (Market Trades), Product and
 //! Currency information, Product Book, and Best Bids and Asks for multiple products.
 
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::Stream;
+use futures::TryStreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
 use crate::constants::products::{
-    BID_ASK_ENDPOINT, CANDLE_MAXIMUM, PRODUCT_BOOK_ENDPOINT, RESOURCE_ENDPOINT,
+    BID_ASK_ENDPOINT, CANDLE_MAXIMUM, PRODUCT_BOOK_ENDPOINT, RESOURCE_ENDPOINT, STREAM_PAGE_SIZE,
 };
 use crate::errors::CbError;
 use crate::http_agent::SecureHttpAgent;
 use crate::models::product::{
-    Candle, CandlesWrapper, Product, ProductBidAskQuery, ProductBook, ProductBookQuery,
-    ProductBookWrapper, ProductBooksWrapper, ProductCandleQuery, ProductListQuery,
-    ProductTickerQuery, ProductsWrapper, Ticker,
+    merge_candles, BidAsk, Candle, CandlesWrapper, Product, ProductBidAskQuery, ProductBook,
+    ProductBookQuery, ProductBookWrapper, ProductBooksWrapper, ProductCandleQuery,
+    ProductListQuery, ProductTickerQuery, ProductType, ProductsWrapper, Ticker,
 };
-use crate::time::{self, Granularity};
-use crate::traits::{HttpAgent, NoQuery, Query};
+use crate::time::{self, Clock, Granularity};
+use crate::traits::{HttpAgent, NoQuery, Paginate, Query};
 use crate::types::CbResult;
+use crate::utils::write_lines;
 
 /// Provides access to the Product API for the service.
+#[derive(Clone)]
 pub struct ProductApi {
     /// Object used to sign requests made to the API.
     agent: Option<SecureHttpAgent>,
+    /// Source of the current time.
+    clock: Arc<dyn Clock>,
 }
 
 impl ProductApi {
@@ -30,8 +42,9 @@ impl ProductApi {
     /// # Arguments
     ///
     /// * `agent` - A agent that include the API Key & Secret along with a client to make requests.
-    pub(crate) fn new(agent: Option<SecureHttpAgent>) -> Self {
-        Self { agent }
+    /// * `clock` - Source of the current time.
+    pub(crate) fn new(agent: Option<SecureHttpAgent>, clock: Arc<dyn Clock>) -> Self {
+        Self { agent, clock }
     }
 
     /// Obtains best bids and asks for a vector of product IDs..
@@ -57,10 +70,7 @@ impl ProductApi {
     pub async fn best_bid_ask(&mut self, query: &ProductBidAskQuery) -> CbResult<Vec<ProductBook>> {
         let agent = get_auth!(self.agent, "get best bid/ask");
         let response = agent.get(BID_ASK_ENDPOINT, query).await?;
-        let data: ProductBooksWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ProductBooksWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -87,13 +97,42 @@ impl ProductApi {
     pub async fn product_book(&mut self, query: &ProductBookQuery) -> CbResult<ProductBook> {
         let agent = get_auth!(self.agent, "get product book");
         let response = agent.get(PRODUCT_BOOK_ENDPOINT, query).await?;
-        let data: ProductBookWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ProductBookWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
+    /// Obtains the best bid and ask for the product ID provided by requesting a single level of
+    /// the product book.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - A string the represents the product's ID.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::NotFound` - If either side of the book is empty.
+    pub async fn top_of_book(&mut self, product_id: &str) -> CbResult<(BidAsk, BidAsk)> {
+        let query = ProductBookQuery::new(product_id).limit(1);
+        let book = self.product_book(&query).await?;
+
+        let bid = book.bids.into_iter().next();
+        let ask = book.asks.into_iter().next();
+        match (bid, ask) {
+            (Some(bid), Some(ask)) => Ok((bid, ask)),
+            _ => Err(CbError::NotFound(format!("top of book for {product_id}"))),
+        }
+    }
+
     /// Obtains a single product based on the Product ID (ex. "BTC-USD").
     ///
     /// # Arguments
@@ -118,10 +157,7 @@ impl ProductApi {
         let agent = get_auth!(self.agent, "get product");
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}");
         let response = agent.get(&resource, &NoQuery).await?;
-        let data: Product = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: Product = agent.parse_json(response).await?;
         Ok(data)
     }
 
@@ -148,13 +184,109 @@ impl ProductApi {
     pub async fn get_bulk(&mut self, query: &ProductListQuery) -> CbResult<Vec<Product>> {
         let agent = get_auth!(self.agent, "get bulk products");
         let response = agent.get(RESOURCE_ENDPOINT, query).await?;
-        let data: ProductsWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ProductsWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
+    /// Creates a paginator that obtains the products matching the query one page at a time.
+    /// Pages are requested using the `limit` and `offset` of the query, a page size of 250 is used
+    /// if no limit is set. Errors from `get_bulk` are returned by `Paginate::next_page`.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Query used to obtain products.
+    pub fn product_pages(&self, query: &ProductListQuery) -> ProductPaginator {
+        ProductPaginator::new(self.clone(), query.clone())
+    }
+
+    /// Lazily obtains every product matching the query, one page at a time. Pages are requested
+    /// using the `limit` and `offset` of the query, a page size of 250 is used if no limit is set.
+    /// The stream ends after the first error is yielded.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Query used to obtain products.
+    ///
+    /// # Errors
+    ///
+    /// Yields the errors of `get_bulk` if a page could not be obtained.
+    pub fn product_stream(&self, query: ProductListQuery) -> impl Stream<Item = CbResult<Product>> {
+        ProductPaginator::new(self.clone(), query).into_stream()
+    }
+
+    /// Obtains the futures contracts that have expired, such as for backtesting against
+    /// historical contracts. Expired contracts are only listed when all products are requested,
+    /// the contracts are then filtered to those with a `contract_expiry` in the past.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn expired_futures(&mut self) -> CbResult<Vec<Product>> {
+        let query = ProductListQuery::new()
+            .product_type(ProductType::Future)
+            .get_all_products(true);
+        let products: Vec<Product> = self.product_stream(query).try_collect().await?;
+
+        let now = self.clock.now();
+        Ok(products
+            .into_iter()
+            .filter(|product| {
+                product.product_type == ProductType::Future
+                    && product
+                        .future_product_details
+                        .as_ref()
+                        .and_then(|details| time::from_rfc3339(&details.contract_expiry).ok())
+                        .is_some_and(|expiry| expiry < now)
+            })
+            .collect())
+    }
+
+    /// Obtains the spot products that can be traded right now, such as for building a trading
+    /// universe at startup. Spot products are requested with their tradability status and then
+    /// filtered with `Product::is_tradeable_now`, removing products that are offline, disabled,
+    /// trading disabled, cancel only, or view only.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn tradable_spot_products(&mut self) -> CbResult<Vec<Product>> {
+        let query = ProductListQuery::new()
+            .product_type(ProductType::Spot)
+            .get_tradability_status(true);
+        let products: Vec<Product> = self.product_stream(query).try_collect().await?;
+
+        Ok(products
+            .into_iter()
+            .filter(|product| {
+                product.product_type == ProductType::Spot && product.is_tradeable_now()
+            })
+            .collect())
+    }
+
     /// Obtains candles for a specific product.
     ///
     /// # Arguments
@@ -184,10 +316,7 @@ impl ProductApi {
         let agent = get_auth!(self.agent, "get candles");
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}/candles");
         let response = agent.get(&resource, query).await?;
-        let data: CandlesWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: CandlesWrapper = agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -215,45 +344,163 @@ impl ProductApi {
         &mut self,
         product_id: &str,
         query: &ProductCandleQuery,
+    ) -> CbResult<Vec<Candle>> {
+        self.candles_ext_cancellable(product_id, query, &CancellationToken::new())
+            .await
+    }
+
+    /// Obtains candles for a specific product extended, stopping early if the token is cancelled.
+    /// The candles obtained before the cancellation are returned, check `cancel.is_cancelled()`
+    /// to determine if the result is partial. A request in progress when the token is cancelled
+    /// is abandoned.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - A string the represents the product's ID.
+    /// * `query` - Span of time to obtain.
+    /// * `cancel` - Token used to stop obtaining additional candles.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn candles_ext_cancellable(
+        &mut self,
+        product_id: &str,
+        query: &ProductCandleQuery,
+        cancel: &CancellationToken,
     ) -> CbResult<Vec<Candle>> {
         is_auth!(self.agent, "get candles extended");
         query.check()?;
 
-        // Extract query parameters.
-        let end_time = query.end;
-        let granularity = query.granularity.clone();
-        let interval_seconds = u64::from(Granularity::to_secs(&granularity));
-        let maximum_candles = u64::from(CANDLE_MAXIMUM);
-
-        // Initialize the span.
-        let mut current_start = query.start;
         let mut all_candles: Vec<Candle> = Vec::new();
-
-        while current_start < end_time {
-            // Calculate the end time for the current batch.
-            let current_end = std::cmp::min(
-                time::after(current_start, interval_seconds * maximum_candles),
-                end_time,
-            );
-
-            // Create a new span for the current batch and fetch candles.
-            let query = ProductCandleQuery {
-                start: current_start,
-                end: current_end,
-                granularity: granularity.clone(),
-                limit: CANDLE_MAXIMUM,
+        for batch in query.batches() {
+            let mut candles = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                candles = self.candles(product_id, &batch) => candles?,
             };
-
-            let mut candles = self.candles(product_id, &query).await?;
             all_candles.append(&mut candles);
-
-            // Update the start time for the next batch.
-            current_start = current_end;
         }
 
         Ok(all_candles)
     }
 
+    /// Obtains candles for a specific product extended, writing them to the writer as
+    /// newline-delimited JSON as each batch arrives instead of holding them in memory. Returns the
+    /// amount of candles written.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - A string the represents the product's ID.
+    /// * `query` - Span of time to obtain.
+    /// * `writer` - Destination of the candles, such as a file or socket.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing a candle.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::WriteError` - If there was an issue writing to the writer.
+    pub async fn candles_to_writer<W>(
+        &mut self,
+        product_id: &str,
+        query: &ProductCandleQuery,
+        mut writer: W,
+    ) -> CbResult<usize>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        is_auth!(self.agent, "write candles");
+        query.check()?;
+
+        let mut written = 0;
+        for batch in query.batches() {
+            let candles = self.candles(product_id, &batch).await?;
+            write_lines(&mut writer, &candles).await?;
+            written += candles.len();
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| CbError::WriteError(e.to_string()))?;
+        Ok(written)
+    }
+
+    /// Obtains the most recent candles for a product, ending with the candle currently in
+    /// progress. The time range is derived from the current time and the granularity.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - A string the represents the product's ID.
+    /// * `granularity` - Span of time each candle represents.
+    /// * `count` - Amount of candles to obtain.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    /// * `CbError::BadQuery` - If the granularity is unknown.
+    pub async fn recent_candles(
+        &mut self,
+        product_id: &str,
+        granularity: Granularity,
+        count: u32,
+    ) -> CbResult<Vec<Candle>> {
+        is_auth!(self.agent, "get recent candles");
+        if count == 0 {
+            return Ok(vec![]);
+        }
+
+        let interval_seconds = u64::from(Granularity::to_secs(&granularity));
+        if interval_seconds == 0 {
+            return Err(CbError::BadQuery(
+                "granularity cannot be unknown or unset".to_string(),
+            ));
+        }
+
+        // Start of the candle in progress, offset by the remaining candles requested. The span
+        // ends with the candle in progress so it is never empty on a granularity boundary.
+        let now = self.clock.now();
+        let current_start = now - (now % interval_seconds);
+        let start = current_start.saturating_sub(u64::from(count - 1) * interval_seconds);
+        let end = time::after(current_start, interval_seconds);
+
+        let query =
+            ProductCandleQuery::new(start, end, granularity).limit(count.min(CANDLE_MAXIMUM));
+        let candles = self.candles_ext(product_id, &query).await?;
+
+        // Sort ascending, removing any overlap between batches.
+        let mut candles = merge_candles(candles, vec![]);
+        let excess = candles.len().saturating_sub(count as usize);
+        candles.drain(..excess);
+        Ok(candles)
+    }
+
     /// Obtains product ticker from the API.
     ///
     /// # Arguments
@@ -283,10 +530,59 @@ impl ProductApi {
         let agent = get_auth!(self.agent, "get ticker");
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}/ticker");
         let response = agent.get(&resource, query).await?;
-        let data: Ticker = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: Ticker = agent.parse_json(response).await?;
         Ok(data)
     }
 }
+
+/// Paginator that obtains products one page at a time, created by `ProductApi::product_pages`.
+pub struct ProductPaginator {
+    /// API used to obtain the pages.
+    api: ProductApi,
+    /// Query used for every page, the limit and offset are replaced per page.
+    query: ProductListQuery,
+    /// Amount of products requested per page.
+    page_size: u32,
+    /// Offset of the next page, `None` once there are no additional pages.
+    offset: Option<u32>,
+}
+
+impl ProductPaginator {
+    /// Creates a paginator starting at the limit and offset of the query.
+    fn new(api: ProductApi, query: ProductListQuery) -> Self {
+        Self {
+            api,
+            page_size: query.limit.unwrap_or(STREAM_PAGE_SIZE),
+            offset: Some(query.offset.unwrap_or(0)),
+            query,
+        }
+    }
+}
+
+#[async_trait]
+impl Paginate for ProductPaginator {
+    type Item = Product;
+
+    async fn next_page(&mut self) -> Option<CbResult<Vec<Product>>> {
+        let offset = self.offset.take()?;
+
+        // An offset of 0 is rejected so it is omitted.
+        let query = ProductListQuery {
+            limit: Some(self.page_size),
+            offset: (offset > 0).then_some(offset),
+            ..self.query.clone()
+        };
+
+        let products = match self.api.get_bulk(&query).await {
+            Ok(products) => products,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // A partial page indicates there are no additional products.
+        let count = u32::try_from(products.len()).unwrap_or(u32::MAX);
+        if count >= self.page_size {
+            self.offset = Some(offset.saturating_add(count));
+        }
+        Some(Ok(products))
+    }
+}
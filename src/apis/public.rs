@@ -3,9 +3,9 @@
 //! `public` gives access to the Public API and the various endpoints associated with it.
 //! Some of the features include getting the API current time in ISO format.
 
-use crate::constants::products::CANDLE_MAXIMUM;
+use tokio_util::sync::CancellationToken;
+
 use crate::constants::public::{PRODUCT_BOOK_ENDPOINT, RESOURCE_ENDPOINT, SERVERTIME_ENDPOINT};
-use crate::errors::CbError;
 use crate::http_agent::PublicHttpAgent;
 use crate::models::product::{
     Candle, CandlesWrapper, Product, ProductBook, ProductBookWrapper, ProductListQuery,
@@ -13,11 +13,11 @@ use crate::models::product::{
 };
 use crate::models::product::{ProductBookQuery, ProductCandleQuery};
 use crate::models::public::ServerTime;
-use crate::time::{self, Granularity};
 use crate::traits::{HttpAgent, NoQuery, Query};
 use crate::types::CbResult;
 
 /// Provides access to the Public API for the service.
+#[derive(Clone)]
 pub struct PublicApi {
     /// Object used to sign requests made to the API.
     agent: PublicHttpAgent,
@@ -49,10 +49,7 @@ impl PublicApi {
     /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_getservertime>
     pub async fn time(&mut self) -> CbResult<ServerTime> {
         let response = self.agent.get(SERVERTIME_ENDPOINT, &NoQuery).await?;
-        let data: ServerTime = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ServerTime = self.agent.parse_json(response).await?;
         Ok(data)
     }
 
@@ -76,10 +73,7 @@ impl PublicApi {
     /// * <https://docs.cdp.coinbase.com/advanced-trade/reference/retailbrokerageapi_getpublicproductbook>
     pub async fn product_book(&mut self, query: &ProductBookQuery) -> CbResult<ProductBook> {
         let response = self.agent.get(PRODUCT_BOOK_ENDPOINT, query).await?;
-        let data: ProductBookWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ProductBookWrapper = self.agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -104,10 +98,7 @@ impl PublicApi {
     pub async fn product(&mut self, product_id: &str) -> CbResult<Product> {
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}");
         let response = self.agent.get(&resource, &NoQuery).await?;
-        let data: Product = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: Product = self.agent.parse_json(response).await?;
         Ok(data)
     }
 
@@ -131,10 +122,7 @@ impl PublicApi {
     /// * <https://docs.cloud.coinbase.com/advanced-trade-api/reference/retailbrokerageapi_getproducts>
     pub async fn products(&mut self, query: &ProductListQuery) -> CbResult<Vec<Product>> {
         let response = self.agent.get(RESOURCE_ENDPOINT, query).await?;
-        let data: ProductsWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: ProductsWrapper = self.agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -164,10 +152,7 @@ impl PublicApi {
     ) -> CbResult<Vec<Candle>> {
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}/candles");
         let response = self.agent.get(&resource, query).await?;
-        let data: CandlesWrapper = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: CandlesWrapper = self.agent.parse_json(response).await?;
         Ok(data.into())
     }
 
@@ -194,38 +179,47 @@ impl PublicApi {
         product_id: &str,
         query: &ProductCandleQuery,
     ) -> CbResult<Vec<Candle>> {
-        query.check()?;
+        self.candles_ext_cancellable(product_id, query, &CancellationToken::new())
+            .await
+    }
 
-        // Extract query parameters.
-        let end_time = query.end;
-        let granularity = query.granularity.clone();
-        let interval_seconds = u64::from(Granularity::to_secs(&granularity));
-        let maximum_candles = u64::from(CANDLE_MAXIMUM);
+    /// Obtains candles for a specific product extended, stopping early if the token is cancelled.
+    /// The candles obtained before the cancellation are returned, check `cancel.is_cancelled()`
+    /// to determine if the result is partial. A request in progress when the token is cancelled
+    /// is abandoned.
+    ///
+    /// NOTE: NOT A STANDARD API FUNCTION. QOL function that may require additional API requests than
+    /// normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - A string the represents the product's ID.
+    /// * `query` - Span of time to obtain.
+    /// * `cancel` - Token used to stop obtaining additional candles.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    pub async fn candles_ext_cancellable(
+        &mut self,
+        product_id: &str,
+        query: &ProductCandleQuery,
+        cancel: &CancellationToken,
+    ) -> CbResult<Vec<Candle>> {
+        query.check()?;
 
-        // Initialize the span.
-        let mut current_start = query.start;
         let mut all_candles: Vec<Candle> = Vec::new();
-
-        while current_start < end_time {
-            // Calculate the end time for the current batch.
-            let current_end = std::cmp::min(
-                time::after(current_start, interval_seconds * maximum_candles),
-                end_time,
-            );
-
-            // Create a new span for the current batch and fetch candles.
-            let query = ProductCandleQuery {
-                start: current_start,
-                end: current_end,
-                granularity: granularity.clone(),
-                limit: CANDLE_MAXIMUM,
+        for batch in query.batches() {
+            let mut candles = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                candles = self.candles(product_id, &batch) => candles?,
             };
-
-            let mut candles = self.candles(product_id, &query).await?;
             all_candles.append(&mut candles);
-
-            // Update the start time for the next batch.
-            current_start = current_end;
         }
 
         Ok(all_candles)
@@ -257,10 +251,7 @@ impl PublicApi {
     ) -> CbResult<Ticker> {
         let resource = format!("{RESOURCE_ENDPOINT}/{product_id}/ticker");
         let response = self.agent.get(&resource, query).await?;
-        let data: Ticker = response
-            .json()
-            .await
-            .map_err(|e| CbError::JsonError(e.to_string()))?;
+        let data: Ticker = self.agent.parse_json(response).await?;
         Ok(data)
     }
 }
@@ -80,7 +80,7 @@ where
     ///
     /// # Returns
     ///
-    /// A vector of `CandleUpdate` sorted by timestamp (newest first).
+    /// A vector of `CandleUpdate` from every event in the message, sorted by timestamp (oldest first).
     fn extract_candle_updates(message: &Message) -> Vec<CandleUpdate> {
         let mut updates: Vec<CandleUpdate> = message
             .events
@@ -95,18 +95,18 @@ where
             .flatten()
             .collect();
 
-        // Sort updates by timestamp (newest first).
-        updates.sort_by(|a, b| b.data.start.cmp(&a.data.start));
+        // Sort updates by timestamp (oldest first).
+        updates.sort_by_key(|update| update.data.start);
         updates
     }
 
-    /// Processes a vector of candle updates.
+    /// Processes a vector of candle updates, oldest first.
     ///
     /// # Arguments
     ///
     /// * `updates` - The sorted vector of `CandleUpdate` to process.
-    async fn process_candle_updates(&mut self, mut updates: Vec<CandleUpdate>) {
-        if let Some(update) = updates.pop() {
+    async fn process_candle_updates(&mut self, updates: Vec<CandleUpdate>) {
+        for update in updates {
             let product_id = update.product_id.clone();
             let new_candle = update.data;
 
@@ -153,7 +153,7 @@ where
                     return; // No updates to process.
                 }
 
-                // Process the most recent update and handle completed candles.
+                // Process every update and handle completed candles.
                 self.process_candle_updates(updates).await;
             }
             Err(err) => {
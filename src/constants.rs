@@ -5,24 +5,43 @@ pub(crate) const API_ROOT_URI: &str = "api.coinbase.com";
 pub(crate) const API_SANDBOX_ROOT_URI: &str = "api-sandbox.coinbase.com";
 pub(crate) const CRATE_USER_AGENT: &str = "cbadv/Rust";
 
+/// JSON Web Token constants
+#[cfg(any(feature = "trade", feature = "websocket"))]
+pub(crate) mod jwt {
+    /// Amount of seconds a token is valid for.
+    pub(crate) const LIFETIME_SECS: u64 = 120;
+
+    /// Cached tokens are replaced when they have fewer than this many seconds remaining.
+    pub(crate) const REFRESH_MARGIN_SECS: u64 = 30;
+}
+
 /// Accounts API constants
 pub(crate) mod accounts {
+    #[cfg(feature = "trade")]
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/accounts";
     pub(crate) const LIST_ACCOUNT_MAXIMUM: u32 = 250;
+    #[cfg(feature = "trade")]
+    pub(crate) const CACHE_TTL_MS: u64 = 1000;
+    #[cfg(feature = "trade")]
+    pub(crate) const LEDGER_ROOT_ENDPOINT: &str = "/v2/accounts";
+    pub(crate) const LIST_TRANSACTIONS_MAXIMUM: u32 = 100;
 }
 
 /// Convert API constants
+#[cfg(feature = "trade")]
 pub(crate) mod convert {
     pub(crate) const QUOTE_ENDPOINT: &str = "/api/v3/brokerage/convert/quote";
     pub(crate) const TRADE_ENDPOINT: &str = "/api/v3/brokerage/convert/trade";
 }
 
 /// Fees API constants
+#[cfg(feature = "trade")]
 pub(crate) mod fees {
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/transaction_summary";
 }
 
 /// Orders API constants
+#[cfg(feature = "trade")]
 pub(crate) mod orders {
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/orders";
     pub(crate) const CANCEL_BATCH_ENDPOINT: &str = "/api/v3/brokerage/orders/batch_cancel";
@@ -32,9 +51,14 @@ pub(crate) mod orders {
     pub(crate) const BATCH_ENDPOINT: &str = "/api/v3/brokerage/orders/historical/batch";
     pub(crate) const FILLS_ENDPOINT: &str = "/api/v3/brokerage/orders/historical/fills";
     pub(crate) const CLOSE_POSITION_ENDPOINT: &str = "/api/v3/brokerage/orders/close_position";
+    pub(crate) const CANCEL_BATCH_MAXIMUM: usize = 100;
+
+    /// Default maximum amount of seconds into the future a GTD order can end, 90 days.
+    pub(crate) const GTD_MAX_HORIZON_SECS: u64 = 90 * 24 * 60 * 60;
 }
 
 /// Portfolios API constants
+#[cfg(feature = "trade")]
 pub(crate) mod portfolios {
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/portfolios";
     pub(crate) const MOVE_FUNDS_ENDPOINT: &str = "/api/v3/brokerage/portfolios/move_funds";
@@ -43,19 +67,31 @@ pub(crate) mod portfolios {
 /// Products API constants
 pub(crate) mod products {
     pub(crate) const CANDLE_MAXIMUM: u32 = 350;
+    #[cfg(feature = "trade")]
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/products";
+    #[cfg(feature = "trade")]
     pub(crate) const BID_ASK_ENDPOINT: &str = "/api/v3/brokerage/best_bid_ask";
+    #[cfg(feature = "trade")]
     pub(crate) const PRODUCT_BOOK_ENDPOINT: &str = "/api/v3/brokerage/product_book";
+    #[cfg(feature = "trade")]
+    pub(crate) const STREAM_PAGE_SIZE: u32 = 250;
 }
 
 /// Payment API constants
+#[cfg(feature = "trade")]
 pub(crate) mod payments {
     pub(crate) const RESOURCE_ENDPOINT: &str = "/api/v3/brokerage/payment_methods";
 }
 
 /// Data API constants
+#[cfg(feature = "trade")]
 pub(crate) mod data {
     pub(crate) const KEY_PERMISSIONS_ENDPOINT: &str = "/api/v3/brokerage/key_permissions";
+    pub(crate) const FUTURES_BALANCE_SUMMARY_ENDPOINT: &str =
+        "/api/v3/brokerage/cfm/balance_summary";
+    pub(crate) const FUTURES_SWEEPS_ENDPOINT: &str = "/api/v3/brokerage/cfm/sweeps";
+    pub(crate) const FUTURES_SWEEPS_SCHEDULE_ENDPOINT: &str =
+        "/api/v3/brokerage/cfm/sweeps/schedule";
 }
 
 /// Public API constants
@@ -66,6 +102,7 @@ pub(crate) mod public {
 }
 
 /// Websocket API constants
+#[cfg(feature = "websocket")]
 pub(crate) mod websocket {
     pub(crate) const PUBLIC_ENDPOINT: &str = "wss://advanced-trade-ws.coinbase.com";
     pub(crate) const SECURE_ENDPOINT: &str = "wss://advanced-trade-ws-user.coinbase.com";
@@ -73,6 +110,10 @@ pub(crate) mod websocket {
     /// Granularity of Candles from the WebSocket Candle subscription.
     /// NOTE: This is a restriction by `CoinBase` and cannot be currently changed (20240125)
     pub(crate) const GRANULARITY: u64 = 300;
+
+    /// Maximum amount of product IDs sent in a single subscription message.
+    #[cfg(feature = "trade")]
+    pub(crate) const SUBSCRIBE_CHUNK_SIZE: usize = 100;
 }
 
 /// Amount of tokens per second refilled.
@@ -81,4 +122,7 @@ pub(crate) mod ratelimits {
     pub(crate) const PUBLIC_REST_REFRESH_RATE: f64 = 10.0;
     pub(crate) const SECURE_WEBSOCKET_REFRESH_RATE: f64 = 750.0;
     pub(crate) const PUBLIC_WEBSOCKET_REFRESH_RATE: f64 = 8.0;
+
+    /// Seconds to wait after being rate limited if the server does not specify.
+    pub(crate) const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
 }
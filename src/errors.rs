@@ -2,6 +2,7 @@
 
 use std::error::Error;
 use std::fmt;
+use std::time::Duration;
 
 /// Types of errors that can occur.
 #[derive(Debug)]
@@ -13,6 +14,8 @@ pub enum CbError {
         code: reqwest::StatusCode,
         body: String,
     },
+    /// Rate limited by the service (HTTP 429), should wait before retrying.
+    RateLimited { retry_after: Duration },
     /// Could not connect to the service.
     BadConnection(String),
     /// Nothing to do.
@@ -37,10 +40,14 @@ pub enum CbError {
     JsonError(String),
     /// Authentication error.
     AuthenticationError(String),
+    /// Credentials were rejected by the service, such as an expired or revoked key.
+    Unauthorized(String),
     /// An invalid query.
     BadQuery(String),
     /// An invalid request.
     BadRequest(String),
+    /// Could not write to the provided writer.
+    WriteError(String),
 }
 
 impl fmt::Display for CbError {
@@ -59,13 +66,18 @@ impl fmt::Display for CbError {
             CbError::BadStatus { code, body } => {
                 write!(f, "HTTP error {}: {}", code.as_u16(), body)
             }
+            CbError::RateLimited { retry_after } => {
+                write!(f, "rate limited, retry after {}ms", retry_after.as_millis())
+            }
             CbError::BadConnection(value) => write!(f, "could not connect: {value}"),
             CbError::RequestError(value) => write!(f, "HTTP request error: {value}"),
             CbError::UrlParseError(value) => write!(f, "URL parse error: {value}"),
             CbError::JsonError(value) => write!(f, "JSON deserialization error: {value}"),
             CbError::AuthenticationError(value) => write!(f, "authentication error: {value}"),
+            CbError::Unauthorized(value) => write!(f, "credentials rejected: {value}"),
             CbError::BadQuery(value) => write!(f, "invalid query: {value}"),
             CbError::BadRequest(value) => write!(f, "invalid request: {value}"),
+            CbError::WriteError(value) => write!(f, "could not write: {value}"),
         }
     }
 }
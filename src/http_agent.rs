@@ -5,17 +5,27 @@
 //! the GET and POST requests.
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use futures::lock::Mutex;
-use reqwest::header::{CONTENT_TYPE, USER_AGENT};
-use reqwest::{Method, Response, Url};
+use chrono::DateTime;
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER, USER_AGENT};
+use reqwest::{Method, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "trade")]
 use serde::Serialize;
+use tokio::sync::{Mutex, Semaphore};
 
+use crate::constants::ratelimits::DEFAULT_RETRY_AFTER_SECS;
 use crate::constants::{API_ROOT_URI, API_SANDBOX_ROOT_URI, CRATE_USER_AGENT};
 use crate::errors::CbError;
+#[cfg(feature = "trade")]
 use crate::jwt::Jwt;
+use crate::strict_json;
+use crate::time::Clock;
 use crate::token_bucket::TokenBucket;
-use crate::traits::{HttpAgent, Query, Request};
+#[cfg(feature = "trade")]
+use crate::traits::Request;
+use crate::traits::{HttpAgent, Query};
 use crate::types::CbResult;
 
 /// Base HTTP Agent that is responsible for making requests and token bucket.
@@ -27,6 +37,12 @@ pub(crate) struct HttpAgentBase {
     bucket: Arc<Mutex<TokenBucket>>,
     /// Root URI for the API.
     root_uri: &'static str,
+    /// Error on fields in responses that are not modelled.
+    strict_json: bool,
+    /// Limits the amount of requests in flight at once, shared by all agents of a client.
+    limiter: Option<Arc<Semaphore>>,
+    /// Source of the current time, used to determine how long to wait when rate limited.
+    clock: Arc<dyn Clock>,
 }
 
 impl HttpAgentBase {
@@ -35,8 +51,15 @@ impl HttpAgentBase {
     /// # Arguments
     ///
     /// * `use_sandbox` - A boolean that determines if the sandbox should be used.
+    /// * `strict_json` - A boolean that determines if unknown fields in responses are errors.
     /// * `shared_bucket` - Shared token bucket for all APIs.
-    pub(crate) fn new(use_sandbox: bool, shared_bucket: Arc<Mutex<TokenBucket>>) -> CbResult<Self> {
+    /// * `clock` - Source of the current time.
+    pub(crate) fn new(
+        use_sandbox: bool,
+        strict_json: bool,
+        shared_bucket: Arc<Mutex<TokenBucket>>,
+        clock: Arc<dyn Clock>,
+    ) -> CbResult<Self> {
         let root_uri = if use_sandbox {
             API_SANDBOX_ROOT_URI
         } else {
@@ -44,7 +67,7 @@ impl HttpAgentBase {
         };
 
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
+            .timeout(Duration::from_secs(10))
             .build()
             .map_err(|e| CbError::RequestError(e.to_string()))?;
 
@@ -52,6 +75,9 @@ impl HttpAgentBase {
             client,
             bucket: shared_bucket,
             root_uri,
+            strict_json,
+            limiter: None,
+            clock,
         })
     }
 
@@ -79,6 +105,7 @@ impl HttpAgentBase {
     /// # Arguments
     ///
     /// * `request` - The request to convert to a JSON string.
+    #[cfg(feature = "trade")]
     fn convert_request<'a, T>(request: &'a T) -> CbResult<String>
     where
         T: Request + Serialize + 'a,
@@ -89,7 +116,9 @@ impl HttpAgentBase {
         Ok(data)
     }
 
-    /// Handles the response from the API.
+    /// Handles the response from the API. Rate limited responses are converted into
+    /// `CbError::RateLimited` using the `Retry-After` header provided by the server, which is
+    /// either an amount of seconds or the HTTP-date to retry at.
     ///
     /// # Arguments
     ///
@@ -97,6 +126,16 @@ impl HttpAgentBase {
     async fn handle_response(&self, response: Response) -> CbResult<Response> {
         if response.status().is_success() {
             Ok(response)
+        } else if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, self.clock.now()))
+                .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+            Err(CbError::RateLimited {
+                retry_after: Duration::from_secs(retry_after),
+            })
         } else {
             let status = response.status();
             let body = response
@@ -107,6 +146,27 @@ impl HttpAgentBase {
         }
     }
 
+    /// Parses the JSON body of a response. In strict mode, fields that are not modelled by `T`
+    /// result in an error instead of being ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response from the API.
+    pub(crate) async fn parse_json<T: DeserializeOwned>(&self, response: Response) -> CbResult<T> {
+        if self.strict_json {
+            let value: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| CbError::JsonError(e.to_string()))?;
+            strict_json::from_value(&value)
+        } else {
+            response
+                .json()
+                .await
+                .map_err(|e| CbError::JsonError(e.to_string()))
+        }
+    }
+
     /// Executes the request to the API.
     ///
     /// # Arguments
@@ -127,6 +187,12 @@ impl HttpAgentBase {
             locked_bucket.wait_on().await;
         }
 
+        // Held until the body is read, the semaphore is never closed.
+        let permit = match &self.limiter {
+            Some(limiter) => limiter.acquire().await.ok(),
+            None => None,
+        };
+
         let mut request = self
             .client
             .request(method, url)
@@ -145,9 +211,58 @@ impl HttpAgentBase {
             .send()
             .await
             .map_err(|e| CbError::RequestError(e.to_string()))?;
+        let response = self.handle_response(response).await?;
+
+        // Download the body while the permit is held so it counts as in flight.
+        match permit {
+            Some(_permit) => buffer_response(response).await,
+            None => Ok(response),
+        }
+    }
+}
+
+/// Reads the entire body of the response, returning an equivalent response backed by the body.
+///
+/// # Arguments
+///
+/// * `response` - The response from the API.
+async fn buffer_response(response: Response) -> CbResult<Response> {
+    let mut buffered = http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(headers) = buffered.headers_mut() {
+        headers.extend(response.headers().clone());
+    }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| CbError::RequestError(e.to_string()))?;
+    buffered
+        .body(body)
+        .map(Response::from)
+        .map_err(|e| CbError::RequestError(e.to_string()))
+}
 
-        self.handle_response(response).await
+/// Parses the `Retry-After` header into the amount of seconds to wait. The header is either an
+/// amount of seconds or an HTTP-date, dates that have already passed result in no wait.
+///
+/// # Arguments
+///
+/// * `value` - Value of the `Retry-After` header.
+/// * `now` - Current time, in seconds since the UNIX epoch.
+fn parse_retry_after(value: &str, now: u64) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
     }
+
+    let retry_at = DateTime::parse_from_rfc2822(value).ok()?.timestamp();
+    Some(
+        u64::try_from(retry_at)
+            .unwrap_or_default()
+            .saturating_sub(now),
+    )
 }
 
 /// Unsigned HTTP Agent that is responsible for making requests without authentication.
@@ -163,15 +278,32 @@ impl PublicHttpAgent {
     /// # Arguments
     ///
     /// * `use_sandbox` - A boolean that determines if the sandbox should be used.
+    /// * `strict_json` - A boolean that determines if unknown fields in responses are errors.
     /// * `shared_bucket` - Shared token bucket for all APIs.
-    pub(crate) fn new(use_sandbox: bool, shared_bucket: Arc<Mutex<TokenBucket>>) -> CbResult<Self> {
+    /// * `clock` - Source of the current time.
+    pub(crate) fn new(
+        use_sandbox: bool,
+        strict_json: bool,
+        shared_bucket: Arc<Mutex<TokenBucket>>,
+        clock: Arc<dyn Clock>,
+    ) -> CbResult<Self> {
         Ok(Self {
-            base: HttpAgentBase::new(use_sandbox, shared_bucket)?,
+            base: HttpAgentBase::new(use_sandbox, strict_json, shared_bucket, clock)?,
         })
     }
+
+    /// Limits the amount of requests in flight at once using the shared semaphore.
+    pub(crate) fn with_limiter(mut self, limiter: Arc<Semaphore>) -> Self {
+        self.base.limiter = Some(limiter);
+        self
+    }
 }
 
 impl HttpAgent for PublicHttpAgent {
+    async fn parse_json<T: DeserializeOwned>(&self, response: Response) -> CbResult<T> {
+        self.base.parse_json(response).await
+    }
+
     async fn get(&mut self, resource: &str, query: &impl Query) -> CbResult<Response> {
         let url = self.base.build_url(resource, query)?;
         self.base
@@ -179,6 +311,7 @@ impl HttpAgent for PublicHttpAgent {
             .await
     }
 
+    #[cfg(feature = "trade")]
     async fn post<'a, T>(
         &mut self,
         resource: &str,
@@ -195,6 +328,7 @@ impl HttpAgent for PublicHttpAgent {
             .await
     }
 
+    #[cfg(feature = "trade")]
     async fn put<'a, T>(
         &mut self,
         resource: &str,
@@ -211,6 +345,7 @@ impl HttpAgent for PublicHttpAgent {
             .await
     }
 
+    #[cfg(feature = "trade")]
     async fn delete(&mut self, resource: &str, query: &impl Query) -> CbResult<Response> {
         let url = self.base.build_url(resource, query)?;
         self.base
@@ -220,15 +355,17 @@ impl HttpAgent for PublicHttpAgent {
 }
 
 /// Creates and signs HTTP Requests to the API.
+#[cfg(feature = "trade")]
 #[derive(Debug, Clone)]
 pub(crate) struct SecureHttpAgent {
-    /// JSON Webtoken Generator, disabled in sandbox mode.
+    /// JSON Webtoken Generator, disabled in sandbox mode without valid credentials.
     jwt: Option<Jwt>,
     /// Base client that is responsible for making the requests.
     base: HttpAgentBase,
 }
 
 /// Responsible for signing and sending HTTP requests.
+#[cfg(feature = "trade")]
 impl SecureHttpAgent {
     /// Creates a new instance of `SecureHttpAgent`.
     ///
@@ -236,31 +373,46 @@ impl SecureHttpAgent {
     ///
     /// * `api_key` - A string that holds the key for the API service.
     /// * `api_secret` - A string that holds the secret for the API service.
+    /// * `api_passphrase` - Passphrase to decrypt the secret with, if it is encrypted.
     /// * `use_sandbox` - A boolean that determines if the sandbox should be used.
     /// * `shared_bucket` - Shared token bucket for all APIs.
+    /// * `clock` - Source of the current time for token generation.
     pub(crate) fn new(
         api_key: &str,
         api_secret: &str,
+        api_passphrase: Option<&str>,
         use_sandbox: bool,
+        strict_json: bool,
         shared_bucket: Arc<Mutex<TokenBucket>>,
+        clock: Arc<dyn Clock>,
     ) -> CbResult<Self> {
-        let jwt = if use_sandbox {
-            // Do not generate JWT in sandbox mode.
-            None
-        } else {
-            Some(
-                Jwt::new(api_key, api_secret)
-                    .map_err(|e| CbError::BadJwt(format!("Error creating JWT: {e}")))?,
-            )
+        let jwt = match Jwt::new(api_key, api_secret, api_passphrase, Arc::clone(&clock)) {
+            Ok(jwt) => Some(jwt),
+            // Sandbox does not require authentication, placeholder credentials are unsigned.
+            Err(_) if use_sandbox => None,
+            Err(e) => return Err(CbError::BadJwt(format!("Error creating JWT: {e}"))),
         };
 
         Ok(Self {
             jwt,
-            base: HttpAgentBase::new(use_sandbox, shared_bucket)?,
+            base: HttpAgentBase::new(use_sandbox, strict_json, shared_bucket, clock)?,
         })
     }
 
-    /// Builds a token for the request. If JWT is not enabled, returns None.
+    /// Enables caching of tokens, reusing them for the same request until they are near expiry.
+    pub(crate) fn with_jwt_cache(mut self) -> Self {
+        self.jwt = self.jwt.map(Jwt::with_cache);
+        self
+    }
+
+    /// Limits the amount of requests in flight at once using the shared semaphore.
+    pub(crate) fn with_limiter(mut self, limiter: Arc<Semaphore>) -> Self {
+        self.base.limiter = Some(limiter);
+        self
+    }
+
+    /// Builds a token for the request. If JWT is not enabled, returns None. The `uri` claim is
+    /// derived from the configured root URI, referencing the sandbox host when it is in use.
     ///
     /// # Arguments
     ///
@@ -274,9 +426,35 @@ impl SecureHttpAgent {
             Ok(None)
         }
     }
+
+    /// Signs and performs a HTTP request with an arbitrary method, used for endpoints that are
+    /// not directly supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method of the request, GET, POST, etc.
+    /// * `resource` - A string representing the resource that is being accessed.
+    /// * `query` - A string containing options / parameters for the URL.
+    /// * `body` - The serialized body of the request, if any.
+    pub(crate) async fn request(
+        &mut self,
+        method: Method,
+        resource: &str,
+        query: &impl Query,
+        body: Option<String>,
+    ) -> CbResult<Response> {
+        let url = self.base.build_url(resource, query)?;
+        let token = self.build_token(&method, resource)?;
+        self.base.execute_request(method, url, body, token).await
+    }
 }
 
+#[cfg(feature = "trade")]
 impl HttpAgent for SecureHttpAgent {
+    async fn parse_json<T: DeserializeOwned>(&self, response: Response) -> CbResult<T> {
+        self.base.parse_json(response).await
+    }
+
     async fn get(&mut self, resource: &str, query: &impl Query) -> CbResult<Response> {
         let url = self.base.build_url(resource, query)?;
         let token = self.build_token(&Method::GET, resource)?;
@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use std::str;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
 use base64::Engine;
@@ -10,8 +11,9 @@ use ring::rand::SystemRandom;
 use ring::signature::{self, EcdsaKeyPair};
 use serde::Serialize;
 
+use crate::constants::jwt::{LIFETIME_SECS, REFRESH_MARGIN_SECS};
 use crate::errors::CbError;
-use crate::time;
+use crate::time::Clock;
 use crate::types::CbResult;
 
 #[derive(Serialize)]
@@ -21,6 +23,9 @@ struct Header<'a> {
     nonce: String,
 }
 
+/// Previously encoded tokens keyed on the `uri` claim, with the time they expire at.
+type TokenCache = HashMap<Option<String>, (u64, String)>;
+
 #[derive(Serialize)]
 struct Payload<'a> {
     sub: String,
@@ -39,6 +44,10 @@ pub(crate) struct Jwt {
     signing_key: Arc<EcdsaKeyPair>,
     /// RNG for signing.
     rng: SystemRandom,
+    /// Source of the current time for token lifetimes.
+    clock: Arc<dyn Clock>,
+    /// Tokens reused until they are near expiry, `None` if caching is disabled.
+    cache: Option<Arc<Mutex<TokenCache>>>,
 }
 
 impl Clone for Jwt {
@@ -47,14 +56,31 @@ impl Clone for Jwt {
             api_key: self.api_key.clone(),
             signing_key: Arc::clone(&self.signing_key),
             rng: SystemRandom::new(),
+            clock: Arc::clone(&self.clock),
+            cache: self.cache.clone(),
         }
     }
 }
 
 impl Jwt {
     /// Create a new instance of the JSON Web Token (Jwt) used to generate new tokens.
-    pub(crate) fn new(api_key: &str, api_secret: &str) -> CbResult<Self> {
-        let secret = Self::format_key(api_secret.as_bytes())?;
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - API key provided by the service.
+    /// * `api_secret` - Private key in PEM format.
+    /// * `passphrase` - Passphrase to decrypt the private key with, if it is encrypted.
+    /// * `clock` - Source of the current time for token lifetimes.
+    pub(crate) fn new(
+        api_key: &str,
+        api_secret: &str,
+        passphrase: Option<&str>,
+        clock: Arc<dyn Clock>,
+    ) -> CbResult<Self> {
+        let secret = match passphrase {
+            Some(passphrase) => Self::decrypt_key(api_secret.as_bytes(), passphrase)?,
+            None => Self::format_key(api_secret.as_bytes())?,
+        };
 
         // Initialize SystemRandom.
         let rng = SystemRandom::new();
@@ -67,9 +93,21 @@ impl Jwt {
             api_key: api_key.to_string(),
             signing_key: Arc::new(signing_key),
             rng,
+            clock,
+            cache: None,
         })
     }
 
+    /// Enables caching of encoded tokens. A token is reused for the same `uri` claim until it
+    /// is near expiry instead of signing a new token for every request. Clones share the cache.
+    pub(crate) fn with_cache(mut self) -> Self {
+        self.cache = Some(Arc::new(Mutex::new(HashMap::new())));
+        self
+    }
+
+    /// Builds the `uri` claim for a request, the root is the configured host for the API such as
+    /// the production or sandbox host.
+    #[cfg(feature = "trade")]
     #[inline]
     pub(crate) fn build_uri(method: &str, root: &str, url: &str) -> String {
         format!("{method} {root}{url}")
@@ -92,12 +130,12 @@ impl Jwt {
 
     /// Creates the payload for the message.
     fn build_payload(&self, uri: Option<&str>) -> Payload<'static> {
-        let now = time::now();
+        let now = self.clock.now();
         Payload {
             sub: self.api_key.clone(),
             iss: "coinbase-cloud",
             nbf: now,
-            exp: now + 120,
+            exp: now + LIFETIME_SECS,
             uri: uri.map(String::from),
         }
     }
@@ -135,8 +173,9 @@ impl Jwt {
     /// A `CbResult<Vec<u8>>` with the parsed key data in binary format if successful;
     /// otherwise, an error.
     fn format_key(key: &[u8]) -> CbResult<Vec<u8>> {
+        // An empty passphrase prevents OpenSSL from prompting for one if the key is encrypted.
         // Check if already in pkcs8 format.
-        if let Ok(pkey) = PKey::private_key_from_pem(key) {
+        if let Ok(pkey) = PKey::private_key_from_pem_passphrase(key, b"") {
             if let Ok(serialized) = pkey.private_key_to_pem_pkcs8() {
                 if serialized == key {
                     return Self::parse_key(key);
@@ -145,7 +184,7 @@ impl Jwt {
         }
 
         // Not in pkcs8 format, attempt conversion.
-        let ec_key = EcKey::private_key_from_pem(key)
+        let ec_key = EcKey::private_key_from_pem_passphrase(key, b"")
             .map_err(|why| CbError::BadPrivateKey(why.to_string()))?;
         let pkey =
             PKey::from_ec_key(ec_key).map_err(|why| CbError::BadPrivateKey(why.to_string()))?;
@@ -157,6 +196,28 @@ impl Jwt {
         Self::parse_key(&new_key)
     }
 
+    /// Decrypts a passphrase-protected private key and converts it into PKCS#8 format.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: A byte slice (`&[u8]`) containing the encrypted private key in PEM format.
+    /// * `passphrase`: Passphrase the private key is encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// A `CbResult<Vec<u8>>` with the decrypted key data in binary format if successful;
+    /// otherwise, an error.
+    fn decrypt_key(key: &[u8], passphrase: &str) -> CbResult<Vec<u8>> {
+        let pkey = PKey::private_key_from_pem_passphrase(key, passphrase.as_bytes())
+            .map_err(|why| CbError::BadPrivateKey(format!("unable to decrypt key: {why}")))?;
+
+        let new_key = pkey
+            .private_key_to_pem_pkcs8()
+            .map_err(|why| CbError::BadPrivateKey(why.to_string()))?;
+
+        Self::parse_key(&new_key)
+    }
+
     /// Parses a PEM-encoded private key or a base64-encoded key.
     ///
     /// This function takes a byte slice representing either a PEM-encoded private key
@@ -219,7 +280,8 @@ impl Jwt {
         Ok(Self::to_base64(signature.as_ref()))
     }
 
-    /// Encodes JWT headers and payload into a signed JWT token.
+    /// Encodes JWT headers and payload into a signed JWT token. If caching is enabled, a
+    /// previously encoded token for the same URI is returned while it is not near expiry.
     ///
     /// # Arguments
     ///
@@ -229,6 +291,36 @@ impl Jwt {
     ///
     /// A `CbResult<String>` with the JWT token if successful; otherwise, an error.
     pub(crate) fn encode(&self, uri: Option<&str>) -> CbResult<String> {
+        let Some(cache) = &self.cache else {
+            return self.sign_token(uri);
+        };
+
+        let key = uri.map(String::from);
+        let now = self.clock.now();
+        if let Ok(tokens) = cache.lock() {
+            if let Some((expires_at, token)) = tokens.get(&key) {
+                if now + REFRESH_MARGIN_SECS < *expires_at {
+                    return Ok(token.clone());
+                }
+            }
+        }
+
+        let token = self.sign_token(uri)?;
+        if let Ok(mut tokens) = cache.lock() {
+            // Drop any tokens that are near expiry to keep the cache bounded.
+            tokens.retain(|_, (expires_at, _)| now + REFRESH_MARGIN_SECS < *expires_at);
+            tokens.insert(key, (now + LIFETIME_SECS, token.clone()));
+        }
+
+        Ok(token)
+    }
+
+    /// Creates a new signed JWT token.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri`: the URI being accessed.
+    fn sign_token(&self, uri: Option<&str>) -> CbResult<String> {
         // Convert the header and payload into base64.
         let header = self.build_header()?.serialize_base64()?;
         let payload = Jwt::base64_encode(&self.build_payload(uri))?;
@@ -256,4 +348,3 @@ impl<'a> Header<'a> {
         Ok(URL_SAFE_NO_PAD.encode(&raw))
     }
 }
-
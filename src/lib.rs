@@ -20,12 +20,16 @@
 #[cfg(feature = "config")]
 pub mod config;
 
+#[cfg(feature = "trade")]
 #[macro_use]
 pub(crate) mod macros;
 
+#[cfg(feature = "websocket")]
 mod candle_watcher;
 pub(crate) mod http_agent;
+#[cfg(any(feature = "trade", feature = "websocket"))]
 pub(crate) mod jwt;
+mod strict_json;
 mod token_bucket;
 
 pub(crate) mod constants;
@@ -34,15 +38,26 @@ pub mod time;
 pub mod traits;
 pub mod types;
 pub(crate) mod utils;
+#[cfg(feature = "websocket")]
 pub use utils::FunctionCallback;
 
 pub mod apis;
 pub mod models;
 
 mod rest;
+#[cfg(feature = "websocket")]
 mod websocket;
+#[cfg(feature = "websocket")]
+mod websocket_pool;
 pub use rest::{RestClient, RestClientBuilder};
-pub use websocket::{WebSocketClient, WebSocketClientBuilder};
+#[cfg(feature = "websocket")]
+pub use websocket::{resilient_feed, WebSocketClient, WebSocketClientBuilder};
+#[cfg(feature = "websocket")]
+pub use websocket_pool::WebSocketPool;
 
 // Re-export async_trait for the end-user.
 pub use async_trait::async_trait;
+// Re-export the HTTP method used for arbitrary requests.
+pub use reqwest::Method;
+// Re-export the token used to cancel multi-request helpers.
+pub use tokio_util::sync::CancellationToken;
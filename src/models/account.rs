@@ -5,7 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::constants::accounts::LIST_ACCOUNT_MAXIMUM;
+use crate::constants::accounts::{LIST_ACCOUNT_MAXIMUM, LIST_TRANSACTIONS_MAXIMUM};
 use crate::errors::CbError;
 use crate::traits::Query;
 use crate::types::CbResult;
@@ -14,7 +14,7 @@ use crate::utils::QueryBuilder;
 use super::shared::Balance;
 
 /// Platform that the account is associated with.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Platform {
     /// Spot account.
     #[serde(rename = "ACCOUNT_PLATFORM_CONSUMER")]
@@ -28,7 +28,7 @@ pub enum Platform {
 }
 
 /// Possible values for the account type.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AccountType {
     #[serde(rename = "ACCOUNT_TYPE_UNSPECIFIED")]
     Unspecified,
@@ -73,6 +73,22 @@ pub struct Account {
     pub platform: Platform,
 }
 
+impl Account {
+    /// Amount of the hold on the account that is not explained by the holds provided, such as
+    /// funds held for a pending withdrawal or as margin. Never negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `holds` - Holds of the account, such as from `AccountApi::open_order_holds`.
+    pub fn unattributed_hold(&self, holds: &[Hold]) -> Balance {
+        let attributed: f64 = holds.iter().map(|hold| hold.amount.value).sum();
+        Balance::new(
+            (self.hold.value - attributed).max(0.0),
+            self.hold.currency.clone(),
+        )
+    }
+}
+
 /// Response from the API that wraps a list of accounts.
 #[derive(Deserialize, Debug)]
 pub struct PaginatedAccounts {
@@ -93,6 +109,8 @@ pub struct AccountListQuery {
     pub limit: u32,
     /// Returns accounts after the cursor provided.
     pub cursor: Option<String>,
+    /// Only accounts belonging to this portfolio are returned. Default is the portfolio of the API key.
+    pub retail_portfolio_id: Option<String>,
 }
 
 impl Query for AccountListQuery {
@@ -109,6 +127,7 @@ impl Query for AccountListQuery {
         QueryBuilder::new()
             .push("limit", self.limit)
             .push_optional("cursor", &self.cursor)
+            .push_optional("retail_portfolio_id", &self.retail_portfolio_id)
             .build()
     }
 }
@@ -118,6 +137,7 @@ impl Default for AccountListQuery {
         Self {
             limit: 49,
             cursor: None,
+            retail_portfolio_id: None,
         }
     }
 }
@@ -139,6 +159,12 @@ impl AccountListQuery {
         self.cursor = Some(cursor);
         self
     }
+
+    /// Sets the portfolio that the accounts belong to. Default is the portfolio of the API key.
+    pub fn retail_portfolio_id(mut self, retail_portfolio_id: &str) -> Self {
+        self.retail_portfolio_id = Some(retail_portfolio_id.to_string());
+        self
+    }
 }
 
 /// Response from the API that wraps a single account.
@@ -153,3 +179,167 @@ impl From<AccountWrapper> for Account {
         wrapper.account
     }
 }
+
+/// Type of a ledger entry (transaction) for an account.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEntryType {
+    /// Crypto purchased.
+    Buy,
+    /// Crypto sold.
+    Sell,
+    /// Crypto sent or received from another wallet.
+    Send,
+    /// Crypto received.
+    Receive,
+    /// Funds moved between accounts.
+    Transfer,
+    /// Fiat deposited.
+    FiatDeposit,
+    /// Fiat withdrawn.
+    FiatWithdrawal,
+    /// Fill of an Advanced Trade order.
+    AdvancedTradeFill,
+    /// Trade between two currencies.
+    Trade,
+    /// Staking or other rewards.
+    StakingReward,
+    /// Any type not recognized by this library.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Status of a ledger entry (transaction) for an account.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEntryStatus {
+    /// Transaction is pending.
+    Pending,
+    /// Transaction completed.
+    Completed,
+    /// Transaction failed.
+    Failed,
+    /// Transaction expired.
+    Expired,
+    /// Transaction was canceled.
+    Canceled,
+    /// Any status not recognized by this library.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Represents a single entry in the transaction ledger of an account, such as a deposit,
+/// withdrawal, trade, or fee.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerEntry {
+    /// Unique identifier for the transaction.
+    pub id: String,
+    /// Type of the transaction.
+    pub r#type: LedgerEntryType,
+    /// Status of the transaction.
+    pub status: LedgerEntryStatus,
+    /// Amount of the transaction in the currency of the account, negative for outgoing.
+    pub amount: Balance,
+    /// Amount of the transaction in the user's native currency.
+    #[serde(default)]
+    pub native_amount: Option<Balance>,
+    /// Description of the transaction.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Time at which the transaction was created.
+    pub created_at: String,
+}
+
+/// Source of funds being held in an account. Only holds for open orders can be itemized.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldType {
+    /// Funds reserved for an open order.
+    Order,
+}
+
+/// Itemized funds on hold in an account, reducing the available balance.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hold {
+    /// Amount being held in the currency of the account.
+    pub amount: Balance,
+    /// Source of the hold.
+    pub r#type: HoldType,
+    /// ID of the order the funds are held for.
+    pub order_id: String,
+    /// Time at which the hold was created.
+    pub created_at: String,
+}
+
+/// Pagination information for the ledger.
+#[cfg(feature = "trade")]
+#[derive(Deserialize, Debug)]
+pub(crate) struct LedgerPagination {
+    /// ID to pass as `starting_after` to obtain the next page, `None` if there are none.
+    pub(crate) next_starting_after: Option<String>,
+}
+
+/// Response from the API that wraps a page of ledger entries.
+#[cfg(feature = "trade")]
+#[derive(Deserialize, Debug)]
+pub(crate) struct LedgerWrapper {
+    /// Pagination information.
+    pub(crate) pagination: LedgerPagination,
+    /// Ledger entries returned from the API.
+    pub(crate) data: Vec<LedgerEntry>,
+}
+
+/// Represents parameters that are optional for the List Account Transactions API request.
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountTransactionsQuery {
+    /// Amount of entries per page, default 25 maximum is 100.
+    pub limit: u32,
+    /// Returns entries after the transaction ID provided.
+    pub starting_after: Option<String>,
+}
+
+impl Query for AccountTransactionsQuery {
+    fn check(&self) -> CbResult<()> {
+        if self.limit == 0 || self.limit > LIST_TRANSACTIONS_MAXIMUM {
+            return Err(CbError::BadQuery(format!(
+                "Limit must be greater than 0 with a maximum of {LIST_TRANSACTIONS_MAXIMUM}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn to_query(&self) -> String {
+        QueryBuilder::new()
+            .push("limit", self.limit)
+            .push_optional("starting_after", &self.starting_after)
+            .build()
+    }
+}
+
+impl Default for AccountTransactionsQuery {
+    fn default() -> Self {
+        Self {
+            limit: 25,
+            starting_after: None,
+        }
+    }
+}
+
+impl AccountTransactionsQuery {
+    /// Creates a new `AccountTransactionsQuery` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the amount of entries per page. Default is 25 and maximum is 100.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets the transaction ID to start after.
+    pub fn starting_after(mut self, starting_after: String) -> Self {
+        self.starting_after = Some(starting_after);
+        self
+    }
+}
@@ -7,14 +7,16 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
 use crate::errors::CbError;
-use crate::traits::{Query, Request};
+use crate::traits::Query;
+#[cfg(feature = "trade")]
+use crate::traits::Request;
 use crate::types::CbResult;
 use crate::utils::QueryBuilder;
 
 use super::shared::Balance;
 
 /// Possible values for the trade status.
-#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TradeStatus {
     /// Unspecified trade status.
     #[serde(rename = "TRADE_STATUS_UNSPECIFIED")]
@@ -185,6 +187,7 @@ pub struct ConvertQuoteRequest {
     pub trade_incentive_metadata: Option<TradeIncentiveMetadata>,
 }
 
+#[cfg(feature = "trade")]
 impl Request for ConvertQuoteRequest {
     fn check(&self) -> CbResult<()> {
         if self.from_account.is_empty() {
@@ -237,6 +240,7 @@ pub struct ConvertQuery {
     /// Sending account.
     pub to_account: String,
 }
+#[cfg(feature = "trade")]
 impl Request for ConvertQuery {
     fn check(&self) -> CbResult<()> {
         if self.from_account.is_empty() {
@@ -5,9 +5,19 @@
 use core::fmt;
 
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
+
+#[cfg(feature = "trade")]
+use crate::errors::CbError;
+#[cfg(feature = "trade")]
+use crate::traits::Request;
+#[cfg(feature = "trade")]
+use crate::types::CbResult;
+
+use super::shared::Balance;
 
 /// Various types of portfolios.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PortfolioType {
     /// Undefined portfolio type.
@@ -51,3 +61,185 @@ pub struct KeyPermissions {
     /// The type of portfolio. Possible values: [UNDEFINED, DEFAULT, CONSUMER, INTX]
     pub portfolio_type: PortfolioType,
 }
+
+/// Margin requirements for a specific margin window for futures (CFM).
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuturesMarginWindow {
+    /// Type of margin window, such as `MARGIN_WINDOW_TYPE_INTRADAY`.
+    pub margin_window_type: String,
+    /// Current margin level, such as `MARGIN_LEVEL_TYPE_BASE`.
+    pub margin_level: String,
+    /// Initial margin required for the window.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub initial_margin: f64,
+    /// Maintenance margin required for the window.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub maintenance_margin: f64,
+    /// Liquidation buffer percentage for the window.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub liquidation_buffer_percentage: f64,
+    /// Total amount on hold for the window.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub total_hold: f64,
+    /// Futures buying power for the window.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub futures_buying_power: f64,
+}
+
+/// Balance summary for futures (CFM), containing collateral and margin information.
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuturesBalanceSummary {
+    /// Amount of buying power available for futures.
+    pub futures_buying_power: Balance,
+    /// Total USD balance across spot (CBI) and futures (CFM).
+    pub total_usd_balance: Balance,
+    /// USD balance held in the spot (CBI) account.
+    pub cbi_usd_balance: Balance,
+    /// USD balance held in the futures (CFM) account.
+    pub cfm_usd_balance: Balance,
+    /// Amount held for open orders.
+    pub total_open_orders_hold_amount: Balance,
+    /// Unrealized profit and loss for open positions.
+    pub unrealized_pnl: Balance,
+    /// Realized profit and loss for the current day.
+    pub daily_realized_pnl: Balance,
+    /// Initial margin required for open positions.
+    pub initial_margin: Balance,
+    /// Margin available for new positions.
+    pub available_margin: Balance,
+    /// Balance at which positions will be liquidated.
+    pub liquidation_threshold: Balance,
+    /// Amount remaining before reaching the liquidation threshold.
+    pub liquidation_buffer_amount: Balance,
+    /// Percentage remaining before reaching the liquidation threshold.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub liquidation_buffer_percentage: f64,
+    /// Margin requirements for the intraday window.
+    pub intraday_margin_window_measure: FuturesMarginWindow,
+    /// Margin requirements for the overnight window.
+    pub overnight_margin_window_measure: FuturesMarginWindow,
+}
+
+/// Response from the API that wraps the futures balance summary.
+#[derive(Deserialize, Debug)]
+pub(crate) struct FuturesBalanceSummaryWrapper {
+    /// Balance summary returned from the API.
+    pub(crate) balance_summary: FuturesBalanceSummary,
+}
+
+impl From<FuturesBalanceSummaryWrapper> for FuturesBalanceSummary {
+    fn from(wrapper: FuturesBalanceSummaryWrapper) -> Self {
+        wrapper.balance_summary
+    }
+}
+
+/// Status of a futures (CFM) sweep.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FuturesSweepStatus {
+    /// Unknown sweep status.
+    #[serde(rename = "UNKNOWN_FCM_SWEEP_STATUS")]
+    Unknown,
+    /// Sweep is pending and can still be cancelled.
+    #[serde(rename = "PENDING")]
+    Pending,
+    /// Sweep is currently being processed.
+    #[serde(rename = "PROCESSING")]
+    Processing,
+}
+
+impl fmt::Display for FuturesSweepStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl AsRef<str> for FuturesSweepStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            FuturesSweepStatus::Unknown => "UNKNOWN_FCM_SWEEP_STATUS",
+            FuturesSweepStatus::Pending => "PENDING",
+            FuturesSweepStatus::Processing => "PROCESSING",
+        }
+    }
+}
+
+/// A sweep of funds from the futures (CFM) account to the spot (CBI) account.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuturesSweep {
+    /// Unique identifier for the sweep.
+    pub id: String,
+    /// Amount requested to be swept.
+    pub requested_amount: Balance,
+    /// Whether all excess funds should be swept.
+    pub should_sweep_all: bool,
+    /// Current status of the sweep.
+    pub status: FuturesSweepStatus,
+    /// Time at which the sweep is scheduled to occur.
+    pub scheduled_time: String,
+}
+
+/// Request to schedule a sweep of funds from the futures (CFM) account to the spot account.
+#[serde_as]
+#[derive(Serialize, Debug, Clone)]
+pub struct FuturesSweepRequest {
+    /// Amount of USD to sweep from the futures account.
+    #[serde_as(as = "DisplayFromStr")]
+    pub usd_amount: f64,
+}
+
+#[cfg(feature = "trade")]
+impl Request for FuturesSweepRequest {
+    fn check(&self) -> CbResult<()> {
+        if self.usd_amount <= 0.0 {
+            return Err(CbError::BadRequest(
+                "usd_amount must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl FuturesSweepRequest {
+    /// Creates a new instance of a request to schedule a futures sweep.
+    ///
+    /// # Arguments
+    ///
+    /// * `usd_amount` - Amount of USD to sweep from the futures account.
+    pub fn new(usd_amount: f64) -> Self {
+        Self { usd_amount }
+    }
+}
+
+/// Response from the API that wraps a list of futures sweeps.
+#[derive(Deserialize, Debug)]
+pub(crate) struct FuturesSweepsWrapper {
+    /// Sweeps returned from the API.
+    pub(crate) sweeps: Vec<FuturesSweep>,
+}
+
+impl From<FuturesSweepsWrapper> for Vec<FuturesSweep> {
+    fn from(wrapper: FuturesSweepsWrapper) -> Self {
+        wrapper.sweeps
+    }
+}
+
+/// Response from the API when scheduling or cancelling a futures sweep.
+#[derive(Deserialize, Debug)]
+pub(crate) struct FuturesSweepSuccessWrapper {
+    /// Whether the request was successful.
+    pub(crate) success: bool,
+}
+
+impl From<FuturesSweepSuccessWrapper> for bool {
+    fn from(wrapper: FuturesSweepSuccessWrapper) -> Self {
+        wrapper.success
+    }
+}
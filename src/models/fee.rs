@@ -33,6 +33,50 @@ pub struct FeeTier {
     pub maker_fee_rate: f64,
 }
 
+impl FeeTier {
+    /// Net edge of a round trip of maker fills at the bid and ask using the maker fee rate of
+    /// the tier. See `round_trip_edge` for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `bid` - Price the buy side is quoted at.
+    /// * `ask` - Price the sell side is quoted at.
+    pub fn round_trip_edge(&self, bid: f64, ask: f64) -> f64 {
+        round_trip_edge(bid, ask, self.maker_fee_rate)
+    }
+}
+
+/// Net edge of a round trip of maker fills, buying at the bid and selling at the ask, as a
+/// fraction of the mid price. This is the spread captured less the maker fee paid on both fills.
+/// A positive edge is profitable after fees. Returns `NaN` if the mid price is not positive.
+///
+/// # Arguments
+///
+/// * `bid` - Price the buy side is quoted at.
+/// * `ask` - Price the sell side is quoted at.
+/// * `maker_rate` - Maker fee rate, such as 0.004 for 0.4%.
+///
+/// ```
+/// use cbadv::models::fee::round_trip_edge;
+///
+/// // A 0.2% spread with a 0.04% maker fee on each fill.
+/// let edge = round_trip_edge(99.9, 100.1, 0.0004);
+/// assert!((edge - 0.0012).abs() < 1e-12);
+///
+/// // The spread does not cover the fees.
+/// assert!(round_trip_edge(99.9, 100.1, 0.004) < 0.0);
+/// assert!(round_trip_edge(0.0, 0.0, 0.004).is_nan());
+/// ```
+pub fn round_trip_edge(bid: f64, ask: f64, maker_rate: f64) -> f64 {
+    let mid = f64::midpoint(bid, ask);
+    if mid <= 0.0 {
+        return f64::NAN;
+    }
+
+    // Fees on both fills sum to the rate applied to twice the mid price.
+    (ask - bid) / mid - 2.0 * maker_rate
+}
+
 /// Represents a decimal number with precision.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -12,4 +12,5 @@ pub mod portfolio;
 pub mod product;
 pub mod public;
 pub mod shared;
+#[cfg(feature = "websocket")]
 pub mod websocket;
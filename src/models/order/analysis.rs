@@ -0,0 +1,183 @@
+//! # Coinbase Advanced Order API
+//!
+//! `order/analysis` contains helpers that compute results from orders and fills.
+
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use chrono::DateTime;
+
+use super::{Fill, Order, OrderSide};
+
+/// Summary of the profit and loss realized from a sequence of fills.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlSummary {
+    /// Realized profit and loss after fees, in the quote currency.
+    pub realized_pnl: f64,
+    /// Realized profit and loss before fees, in the quote currency.
+    pub gross_pnl: f64,
+    /// Total fees paid for all fills, in the quote currency.
+    pub total_fees: f64,
+    /// Base quantity that remains open. Positive for a long position, negative for short.
+    pub open_quantity: f64,
+}
+
+/// A lot of base quantity opened at a specific price.
+struct Lot {
+    side: OrderSide,
+    size: f64,
+    price: f64,
+}
+
+/// Removes adjusted fills, leaving only fills that are in effect. A `REVERSAL` removes the prior
+/// fill with the same trade ID and a `CORRECTION` replaces it.
+fn effective_fills(fills: &[Fill]) -> Vec<&Fill> {
+    let mut effective: Vec<&Fill> = Vec::with_capacity(fills.len());
+    for fill in fills {
+        match fill.trade_type.as_str() {
+            "REVERSAL" | "CORRECTION" => {
+                if let Some(index) = effective
+                    .iter()
+                    .rposition(|prior| prior.trade_id == fill.trade_id)
+                {
+                    effective.remove(index);
+                }
+
+                if fill.trade_type == "CORRECTION" {
+                    effective.push(fill);
+                }
+            }
+            _ => effective.push(fill),
+        }
+    }
+    effective
+}
+
+/// Collapses adjusted fills against the fills they adjust, returning the economically net set of
+/// fills. Adjusted fills share a trade ID with the original but carry a different entry ID, so
+/// summing raw fills double-counts them. A `REVERSAL` removes the prior fill with the same trade
+/// ID and a `CORRECTION` replaces it. Other fills are kept in their original order.
+///
+/// NOTE: Fills are processed in the order provided, they should be sorted oldest first.
+///
+/// # Arguments
+///
+/// * `fills` - Fills sorted oldest first.
+///
+/// # Example
+///
+/// ```rust
+/// use cbadv::models::order::{net_fills, Fill};
+///
+/// let fills: Vec<Fill> = serde_json::from_str(r#"[
+///     {"entry_id": "a", "trade_id": "1", "order_id": "order", "trade_time": "", "trade_type": "FILL", "price": "100", "size": "1", "commission": "0", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"},
+///     {"entry_id": "b", "trade_id": "2", "order_id": "order", "trade_time": "", "trade_type": "FILL", "price": "100", "size": "2", "commission": "0", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"},
+///     {"entry_id": "c", "trade_id": "1", "order_id": "order", "trade_time": "", "trade_type": "REVERSAL", "price": "100", "size": "1", "commission": "0", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"},
+///     {"entry_id": "d", "trade_id": "2", "order_id": "order", "trade_time": "", "trade_type": "CORRECTION", "price": "100", "size": "1.5", "commission": "0", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"}
+/// ]"#).unwrap();
+///
+/// let net = net_fills(&fills);
+/// assert_eq!(net.len(), 1);
+/// assert_eq!(net[0].entry_id, "d");
+/// assert_eq!(net[0].size, 1.5);
+/// ```
+pub fn net_fills(fills: &[Fill]) -> Vec<Fill> {
+    effective_fills(fills).into_iter().cloned().collect()
+}
+
+/// Computes the realized profit and loss for a sequence of fills for a single product, matching
+/// buys against sells in a first-in, first-out (FIFO) order. Fees are subtracted from the
+/// realized profit and loss. Adjusted fills (`REVERSAL` and `CORRECTION`) are netted out against
+/// the fill they adjust.
+///
+/// NOTE: Fills are processed in the order provided, they should be sorted oldest first.
+///
+/// # Arguments
+///
+/// * `fills` - Fills for a single product, sorted oldest first.
+///
+/// # Example
+///
+/// ```rust
+/// use cbadv::models::order::{realized_pnl, Fill};
+///
+/// let fills: Vec<Fill> = serde_json::from_str(r#"[
+///     {"entry_id": "1", "trade_id": "1", "order_id": "1", "trade_time": "", "trade_type": "FILL", "price": "100", "size": "2", "commission": "1", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"},
+///     {"entry_id": "2", "trade_id": "2", "order_id": "2", "trade_time": "", "trade_type": "FILL", "price": "110", "size": "1", "commission": "1", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "BUY"},
+///     {"entry_id": "3", "trade_id": "3", "order_id": "3", "trade_time": "", "trade_type": "FILL", "price": "120", "size": "2.5", "commission": "1", "product_id": "BTC-USD", "sequence_timestamp": "", "liquidity_indicator": "MAKER", "size_in_quote": false, "user_id": "", "side": "SELL"}
+/// ]"#).unwrap();
+///
+/// let summary = realized_pnl(&fills);
+/// assert_eq!(summary.gross_pnl, 45.0);
+/// assert_eq!(summary.total_fees, 3.0);
+/// assert_eq!(summary.realized_pnl, 42.0);
+/// assert_eq!(summary.open_quantity, 0.5);
+/// ```
+pub fn realized_pnl(fills: &[Fill]) -> PnlSummary {
+    let mut summary = PnlSummary::default();
+    let mut lots: VecDeque<Lot> = VecDeque::new();
+
+    for fill in effective_fills(fills) {
+        if fill.side == OrderSide::Unknown {
+            continue;
+        }
+
+        summary.total_fees += fill.commission;
+        let mut remaining = fill.base_size();
+
+        // Close open lots on the opposing side, oldest first.
+        while remaining > 0.0 {
+            let Some(lot) = lots.front_mut() else {
+                break;
+            };
+
+            if lot.side == fill.side {
+                break;
+            }
+
+            let matched = remaining.min(lot.size);
+            summary.gross_pnl += match lot.side {
+                OrderSide::Buy => (fill.price - lot.price) * matched,
+                _ => (lot.price - fill.price) * matched,
+            };
+
+            lot.size -= matched;
+            remaining -= matched;
+            if lot.size <= 0.0 {
+                lots.pop_front();
+            }
+        }
+
+        // Any remaining quantity opens a new lot.
+        if remaining > 0.0 {
+            lots.push_back(Lot {
+                side: fill.side,
+                size: remaining,
+                price: fill.price,
+            });
+        }
+    }
+
+    summary.open_quantity = lots
+        .iter()
+        .map(|lot| match lot.side {
+            OrderSide::Buy => lot.size,
+            _ => -lot.size,
+        })
+        .sum();
+    summary.realized_pnl = summary.gross_pnl - summary.total_fees;
+    summary
+}
+
+/// Sorts orders by their `created_time` with the newest first, such as after obtaining them with
+/// `OrderApi::get_all` where the order of the API is preserved. Orders with a creation time that
+/// cannot be parsed are placed last.
+///
+/// # Arguments
+///
+/// * `orders` - Orders to sort.
+pub fn sort_by_created_time(orders: &mut [Order]) {
+    orders.sort_by_cached_key(|order| {
+        Reverse(DateTime::parse_from_rfc3339(&order.created_time).ok())
+    });
+}
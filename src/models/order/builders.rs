@@ -2,7 +2,15 @@
 //!
 //! `order/builders` provides a builder pattern for creating `CreateOrder` instances.
 
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::constants::orders::GTD_MAX_HORIZON_SECS;
 use crate::errors::CbError;
+use crate::models::product::{check_product_ids, Product};
+use crate::time::{self, Clock, SystemClock};
 use crate::types::CbResult;
 
 use super::{
@@ -28,9 +36,16 @@ pub struct OrderCreateBuilder {
     stop_price: Option<f64>,
     stop_trigger_price: Option<f64>,
     end_time: Option<String>,
+    good_for: Option<Duration>,
     post_only: Option<bool>,
     stop_direction: Option<StopDirection>,
     client_order_id: Option<String>,
+    reduce_only: Option<bool>,
+    self_trade_prevention_id: Option<String>,
+    preview_id: Option<String>,
+    validate_product_id: bool,
+    clock: Arc<dyn Clock>,
+    max_end_time_horizon: Duration,
 }
 
 impl OrderCreateBuilder {
@@ -60,9 +75,16 @@ impl OrderCreateBuilder {
             stop_price: None,
             stop_trigger_price: None,
             end_time: None,
+            good_for: None,
             post_only: None,
             stop_direction: None,
             client_order_id: None,
+            reduce_only: None,
+            self_trade_prevention_id: None,
+            preview_id: None,
+            validate_product_id: false,
+            clock: Arc::new(SystemClock),
+            max_end_time_horizon: Duration::from_secs(GTD_MAX_HORIZON_SECS),
         }
     }
 
@@ -120,7 +142,9 @@ impl OrderCreateBuilder {
         self
     }
 
-    /// Sets the quote size for the order.
+    /// Sets the quote size for the order. Used by market orders, and by limit GTC and GTD orders
+    /// for products that support quote sizing. `OrderCreateRequest::check_product` validates the
+    /// order against the product.
     ///
     /// # Arguments
     ///
@@ -129,15 +153,50 @@ impl OrderCreateBuilder {
     /// # Example
     ///
     /// ```rust
-    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide};
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
     /// let builder = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
     ///     .quote_size(1000.0);
+    ///
+    /// // $500 worth at a limit price of $50,000.
+    /// let order = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Limit)
+    ///     .time_in_force(TimeInForce::GoodUntilCancelled)
+    ///     .quote_size(500.0)
+    ///     .limit_price(50000.0)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(order.order_configuration.quote_size(), Some(500.0));
+    /// assert_eq!(order.order_configuration.base_size(), None);
     /// ```
     pub fn quote_size(mut self, quote_size: f64) -> Self {
         self.quote_size = Some(quote_size);
         self
     }
 
+    /// Raises sizes below the minimums of the product to the minimum. A base size below
+    /// `base_min_size` and a quote size below `quote_min_size` are set to the minimum, sizes that
+    /// are already compliant are left unchanged. Sizes that are zero or negative are not raised
+    /// and are still rejected by the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `product` - Product the order is placed on, provides the minimums.
+    pub fn bump_to_minimum(mut self, product: &Product) -> Self {
+        let bump = |size: f64, minimum: f64| {
+            if size > 0.0 && size < minimum {
+                minimum
+            } else {
+                size
+            }
+        };
+
+        self.base_size = self.base_size.map(|size| bump(size, product.base_min_size));
+        self.quote_size = self
+            .quote_size
+            .map(|size| bump(size, product.quote_min_size));
+        self
+    }
+
     /// Sets the limit price for the order.
     ///
     /// # Arguments
@@ -207,10 +266,62 @@ impl OrderCreateBuilder {
     /// ```
     pub fn end_time(mut self, end_time: &str) -> Self {
         self.end_time = Some(end_time.to_string());
+        self.good_for = None;
         self
     }
 
-    /// Sets the post-only flag for the order.
+    /// Sets the order to be Good 'til Date, ending after the duration has passed. The end time is
+    /// computed from the builder's clock when the order is built, rounded up to the next whole
+    /// second. Replaces any end time that was previously set.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Amount of time the order remains open for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use cbadv::models::order::{OrderConfiguration, OrderCreateBuilder, OrderSide, OrderType};
+    /// let order = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Limit)
+    ///     .base_size(0.5)
+    ///     .limit_price(50000.0)
+    ///     .good_for(Duration::from_secs(5 * 60))
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(order.order_configuration, OrderConfiguration::LimitGtd(_)));
+    /// ```
+    pub fn good_for(mut self, duration: Duration) -> Self {
+        self.time_in_force = Some(TimeInForce::GoodUntilDate);
+        self.good_for = Some(duration);
+        self.end_time = None;
+        self
+    }
+
+    /// Sets the end time for the order from a UTC `DateTime`, formatted as RFC3339 with a
+    /// trailing `Z` and whole seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `end_time` - The end time as a UTC `DateTime`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Utc};
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide};
+    /// let end_time = Utc.with_ymd_and_hms(2024, 12, 31, 23, 59, 59).unwrap();
+    /// let builder = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .end_time_dt(end_time);
+    /// ```
+    pub fn end_time_dt(self, end_time: DateTime<Utc>) -> Self {
+        let end_time = end_time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.end_time(&end_time)
+    }
+
+    /// Sets the post-only flag for the order. Post-only orders are only valid with a time in
+    /// force of Good 'til Cancelled or Good 'til Date, building fails otherwise.
     ///
     /// # Arguments
     ///
@@ -223,6 +334,17 @@ impl OrderCreateBuilder {
     /// let builder = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
     ///     .post_only(true);
     /// ```
+    ///
+    /// ```rust
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
+    /// let result = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Market)
+    ///     .time_in_force(TimeInForce::ImmediateOrCancel)
+    ///     .base_size(0.1)
+    ///     .post_only(true)
+    ///     .build();
+    /// assert!(result.is_err());
+    /// ```
     pub fn post_only(mut self, post_only: bool) -> Self {
         self.post_only = Some(post_only);
         self
@@ -264,6 +386,155 @@ impl OrderCreateBuilder {
         self
     }
 
+    /// Sets the reduce-only flag for the order. Reduce-only orders can only decrease the size of
+    /// an existing position and are only supported for Market and Limit orders. Whether there is a
+    /// position to reduce is enforced by the API, `OrderApi::create_reduce_only` checks the order
+    /// against the open position before it is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `reduce_only` - A boolean indicating whether the order may only reduce a position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide};
+    /// let builder = OrderCreateBuilder::new("BIT-31JAN25-CDE", OrderSide::Sell)
+    ///     .reduce_only(true);
+    /// ```
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = Some(reduce_only);
+        self
+    }
+
+    /// Checks the product ID is in the `BASE-QUOTE` format when the order is built, catching typos
+    /// such as `BTCUSD` without a request to the API. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `validate` - A boolean indicating whether the product ID is checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cbadv::errors::CbError;
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
+    /// let result = OrderCreateBuilder::new("BTCUSD", OrderSide::Buy)
+    ///     .order_type(OrderType::Market)
+    ///     .time_in_force(TimeInForce::ImmediateOrCancel)
+    ///     .base_size(0.5)
+    ///     .validate_product_id(true)
+    ///     .build();
+    /// assert!(matches!(result, Err(CbError::BadQuery(_))));
+    /// ```
+    pub fn validate_product_id(mut self, validate: bool) -> Self {
+        self.validate_product_id = validate;
+        self
+    }
+
+    /// Sets the self-trade prevention ID for the order. Orders sharing the same ID are prevented
+    /// from trading against each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `self_trade_prevention_id` - Identifier shared by the orders that must not self-trade.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
+    /// let order = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Limit)
+    ///     .time_in_force(TimeInForce::GoodUntilCancelled)
+    ///     .base_size(0.5)
+    ///     .limit_price(50000.0)
+    ///     .self_trade_prevention_id("market-maker-1")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(order.self_trade_prevention_id.as_deref(), Some("market-maker-1"));
+    /// ```
+    pub fn self_trade_prevention_id(mut self, self_trade_prevention_id: &str) -> Self {
+        self.self_trade_prevention_id = Some(self_trade_prevention_id.to_string());
+        self
+    }
+
+    /// Sets the ID of a previous preview of the order, obtained from `OrderApi::preview_create`.
+    /// Linking the order to its preview ensures it is created with the same margin and leverage
+    /// that were previewed. Cannot be used when building a preview order.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview_id` - The `preview_id` of the `OrderCreatePreview`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
+    /// let order = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Market)
+    ///     .time_in_force(TimeInForce::ImmediateOrCancel)
+    ///     .quote_size(100.0)
+    ///     .preview_id("b40bbff9-17ce-4726-8b64-9de7ae57ad26")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     order.preview_id.as_deref(),
+    ///     Some("b40bbff9-17ce-4726-8b64-9de7ae57ad26")
+    /// );
+    /// ```
+    pub fn preview_id(mut self, preview_id: &str) -> Self {
+        self.preview_id = Some(preview_id.to_string());
+        self
+    }
+
+    /// Sets the clock used to validate time-sensitive fields such as `end_time`. Defaults to the
+    /// system clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Clock that implements the `Clock` trait.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide};
+    /// use cbadv::time::SystemClock;
+    /// let builder = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .clock(Arc::new(SystemClock));
+    /// ```
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the maximum amount of time into the future that the `end_time` of a GTD order can
+    /// be, orders that end later are rejected by the API. Defaults to 90 days.
+    ///
+    /// # Arguments
+    ///
+    /// * `horizon` - Maximum amount of time from now until the order ends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use cbadv::models::order::{OrderCreateBuilder, OrderSide, OrderType, TimeInForce};
+    /// let result = OrderCreateBuilder::new("BTC-USD", OrderSide::Buy)
+    ///     .order_type(OrderType::Limit)
+    ///     .time_in_force(TimeInForce::GoodUntilDate)
+    ///     .base_size(0.5)
+    ///     .limit_price(50000.0)
+    ///     .end_time("2999-12-31T23:59:59Z")
+    ///     .max_end_time_horizon(Duration::from_secs(30 * 24 * 60 * 60))
+    ///     .build();
+    /// assert!(result.is_err());
+    /// ```
+    pub fn max_end_time_horizon(mut self, horizon: Duration) -> Self {
+        self.max_end_time_horizon = horizon;
+        self
+    }
+
     /// Sets whether the order is a preview order.
     ///
     /// # Arguments
@@ -296,7 +567,8 @@ impl OrderCreateBuilder {
     /// # Errors
     ///
     /// Returns `CbError::BadParse` if required parameters are missing or if the combination
-    /// of `order_type` and `time_in_force` is unsupported.
+    /// of `order_type` and `time_in_force` is unsupported. Returns `CbError::BadQuery` if the
+    /// product ID is validated and not in the `BASE-QUOTE` format.
     ///
     /// # Example
     ///
@@ -309,9 +581,15 @@ impl OrderCreateBuilder {
     ///     .limit_price(50000.0)
     ///     .build();
     /// ```
-    pub fn build(self) -> CbResult<OrderCreateRequest> {
+    pub fn build(mut self) -> CbResult<OrderCreateRequest> {
         self.validate_common_fields()?;
 
+        // Resolve a relative end time against the clock at the time of building.
+        if let Some(duration) = self.good_for {
+            let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+            self.end_time = Some(time::to_rfc3339(self.clock.now().saturating_add(seconds))?);
+        }
+
         let order_configuration = self.determine_order_configuration()?;
 
         let client_order_id = if self.is_preview {
@@ -327,6 +605,9 @@ impl OrderCreateBuilder {
             side: self.side,
             is_preview: self.is_preview,
             order_configuration,
+            reduce_only: self.reduce_only,
+            self_trade_prevention_id: self.self_trade_prevention_id,
+            preview_id: self.preview_id,
         })
     }
 
@@ -342,6 +623,10 @@ impl OrderCreateBuilder {
             return Err(CbError::BadParse("Product ID cannot be empty.".to_string()));
         }
 
+        if self.validate_product_id {
+            check_product_ids([&self.product_id])?;
+        }
+
         if self.order_type.is_none() || self.order_type == Some(OrderType::Unknown) {
             return Err(CbError::BadParse(
                 "Order type must be specified.".to_string(),
@@ -354,6 +639,43 @@ impl OrderCreateBuilder {
             ));
         }
 
+        if self.post_only == Some(true)
+            && !matches!(
+                self.time_in_force,
+                Some(TimeInForce::GoodUntilCancelled | TimeInForce::GoodUntilDate)
+            )
+        {
+            return Err(CbError::BadParse(
+                "Post only requires a time in force of GTC or GTD.".to_string(),
+            ));
+        }
+
+        if self.reduce_only == Some(true)
+            && !matches!(self.order_type, Some(OrderType::Market | OrderType::Limit))
+        {
+            return Err(CbError::BadParse(
+                "Reduce only is only supported for Market and Limit orders.".to_string(),
+            ));
+        }
+
+        if let Some(stp_id) = &self.self_trade_prevention_id {
+            if stp_id.trim().is_empty() {
+                return Err(CbError::BadParse(
+                    "Self-trade prevention ID cannot be empty.".to_string(),
+                ));
+            }
+        }
+
+        if let Some(preview_id) = &self.preview_id {
+            if preview_id.trim().is_empty() {
+                return Err(CbError::BadParse("Preview ID cannot be empty.".to_string()));
+            } else if self.is_preview {
+                return Err(CbError::BadParse(
+                    "Preview ID cannot be set on a preview order.".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -415,11 +737,12 @@ impl OrderCreateBuilder {
 
     /// Validates and constructs `LimitGtc` configuration.
     fn build_limit_gtc(&self) -> Result<OrderConfiguration, CbError> {
-        let base_size = require_field(self.base_size, "base_size")?;
+        let (base_size, quote_size) = self.require_limit_size()?;
         let limit_price = require_field(self.limit_price, "limit_price")?;
 
         Ok(OrderConfiguration::LimitGtc(LimitGtc {
             base_size,
+            quote_size,
             limit_price,
             post_only: self.post_only.unwrap_or(false),
         }))
@@ -427,12 +750,13 @@ impl OrderCreateBuilder {
 
     /// Validates and constructs `LimitGtd` configuration.
     fn build_limit_gtd(&self) -> Result<OrderConfiguration, CbError> {
-        let base_size = require_field(self.base_size, "base_size")?;
+        let (base_size, quote_size) = self.require_limit_size()?;
         let limit_price = require_field(self.limit_price, "limit_price")?;
-        let end_time = require_field_ref(&self.end_time, "end_time")?;
+        let end_time = self.require_end_time()?;
 
         Ok(OrderConfiguration::LimitGtd(LimitGtd {
             base_size,
+            quote_size,
             limit_price,
             end_time: end_time.clone(),
             post_only: self.post_only.unwrap_or(false),
@@ -460,7 +784,7 @@ impl OrderCreateBuilder {
         let limit_price = require_field(self.limit_price, "limit_price")?;
         let stop_price = require_field(self.stop_price, "stop_price")?;
         let stop_direction = require_field(self.stop_direction, "stop_direction")?;
-        let end_time = require_field_ref(&self.end_time, "end_time")?;
+        let end_time = self.require_end_time()?;
 
         Ok(OrderConfiguration::StopLimitGtd(StopLimitGtd {
             base_size,
@@ -472,6 +796,49 @@ impl OrderCreateBuilder {
     }
 }
 
+impl OrderCreateBuilder {
+    /// Validates that exactly one of the base or quote size is present for a limit order. Returns
+    /// the sizes as `(base_size, quote_size)`.
+    fn require_limit_size(&self) -> Result<(Option<f64>, Option<f64>), CbError> {
+        match (self.base_size, self.quote_size) {
+            (Some(_), Some(_)) => Err(CbError::BadParse(
+                "Only one of base_size or quote_size can be provided for Limit orders.".to_string(),
+            )),
+            (None, None) => Err(CbError::BadParse(
+                "Either base_size or quote_size must be provided for Limit orders.".to_string(),
+            )),
+            sizes => Ok(sizes),
+        }
+    }
+
+    /// Validates that the end time is present, is an RFC3339 timestamp, and is in the future
+    /// within the maximum horizon according to the builder's clock.
+    fn require_end_time(&self) -> Result<&String, CbError> {
+        let end_time = require_field_ref(&self.end_time, "end_time")?;
+        let parsed = DateTime::parse_from_rfc3339(end_time).map_err(|e| {
+            CbError::BadParse(format!("end_time must be an RFC3339 timestamp: {e}"))
+        })?;
+
+        let now = i64::try_from(self.clock.now()).unwrap_or(i64::MAX);
+        if parsed.timestamp() <= now {
+            return Err(CbError::BadParse(
+                "end_time must be in the future.".to_string(),
+            ));
+        }
+
+        let horizon = self.max_end_time_horizon.as_secs();
+        let latest = now.saturating_add(i64::try_from(horizon).unwrap_or(i64::MAX));
+        if parsed.timestamp() > latest {
+            return Err(CbError::BadParse(format!(
+                "end_time must be within {horizon} seconds ({} days) of now.",
+                horizon / 86_400
+            )));
+        }
+
+        Ok(end_time)
+    }
+}
+
 /// Validates that a required field is present and returns it, or an error if it is missing.
 fn require_field<T>(field: Option<T>, field_name: &str) -> Result<T, CbError> {
     field.ok_or_else(|| CbError::BadParse(format!("{field_name} is required.")))
@@ -6,13 +6,15 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::utils::format_decimal;
+
 use super::{
     LimitFok, LimitGtc, LimitGtd, MarketIoc, SorLimitIoc, StopLimitGtc, StopLimitGtd,
     TriggerBracketGtc, TriggerBracketGtd,
 };
 
 /// Various order types.
-#[derive(Serialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum OrderType {
     /// Unknown order type.
     #[serde(rename = "UNKNOWN_ORDER_TYPE")]
@@ -49,7 +51,7 @@ impl AsRef<str> for OrderType {
 }
 
 /// Order side, BUY or SELL.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum OrderSide {
     /// Unknown order side. Only used by remote API.
@@ -78,7 +80,7 @@ impl AsRef<str> for OrderSide {
 }
 
 /// Used to sort results.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderSortBy {
     /// Unknown sort by.
@@ -113,7 +115,7 @@ impl AsRef<str> for OrderSortBy {
 }
 
 /// Order status, OPEN, CANCELLED, and EXPIRED.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStatus {
     /// Order is pending.
@@ -158,7 +160,7 @@ impl AsRef<str> for OrderStatus {
         }
     }
 }
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum StopDirection {
     /// Unknown stop direction.
     #[serde(rename = "UNKNOWN_STOP_DIRECTION")]
@@ -177,7 +179,7 @@ impl fmt::Display for StopDirection {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TimeInForce {
     /// Unknown time in force.
@@ -212,19 +214,32 @@ impl AsRef<str> for TimeInForce {
     }
 }
 
-/// Enum representing the different possible trigger statuses.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+/// Enum representing the different possible trigger statuses. Statuses that are not recognized
+/// deserialize as `Unknown`.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::order::TriggerStatus;
+///
+/// let status: TriggerStatus = serde_json::from_str(r#""STOP_TRIGGERED""#).unwrap();
+/// assert_eq!(status, TriggerStatus::StopTriggered);
+///
+/// let status: TriggerStatus = serde_json::from_str(r#""SOMETHING_NEW""#).unwrap();
+/// assert_eq!(status, TriggerStatus::Unknown);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TriggerStatus {
-    /// Unknown time in force.
-    #[serde(rename = "UNKNOWN_TRIGGER_STATUS")]
-    Unknown,
     /// Invalid order type.
     InvalidOrderType,
     /// Stop pending.
     StopPending,
     /// Stop triggered.
     StopTriggered,
+    /// Unknown trigger status, also used for any status not recognized by this library.
+    #[serde(rename = "UNKNOWN_TRIGGER_STATUS", other)]
+    Unknown,
 }
 
 impl fmt::Display for TriggerStatus {
@@ -245,7 +260,7 @@ impl AsRef<str> for TriggerStatus {
 }
 
 /// Enum representing reasons for rejecting an order.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RejectReason {
     /// Unspecified reject reason.
@@ -281,8 +296,294 @@ impl AsRef<str> for RejectReason {
     }
 }
 
+/// Reason an order was cancelled, parsed from the free-text cancel message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum CancelReason {
+    /// Cancelled at the request of the user.
+    UserRequested,
+    /// Cancelled due to insufficient funds.
+    InsufficientFunds,
+    /// Cancelled to prevent the order trading against another order of the same user.
+    SelfTradePrevention,
+    /// Good 'til Date (time) order reached its end time.
+    Expired,
+    /// Post-only order would have taken liquidity.
+    PostOnly,
+    /// Remaining quantity of an Immediate or Cancel / Fill or Kill order was cancelled.
+    ImmediateOrCancel,
+    /// Message did not match a known reason, contains the raw message.
+    Other(String),
+}
+
+impl CancelReason {
+    /// Parses a cancel message into a reason, falling back to `Other` containing the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Cancel message received from the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::order::CancelReason;
+    ///
+    /// assert_eq!(CancelReason::parse("User requested cancel"), CancelReason::UserRequested);
+    /// assert_eq!(
+    ///     CancelReason::parse("Self trade prevention"),
+    ///     CancelReason::SelfTradePrevention
+    /// );
+    /// assert_eq!(
+    ///     CancelReason::parse("Something new"),
+    ///     CancelReason::Other("Something new".to_string())
+    /// );
+    /// ```
+    pub fn parse(message: &str) -> Self {
+        let normalized = message.to_lowercase().replace(['_', '-'], " ");
+        let contains = |needles: &[&str]| needles.iter().any(|n| normalized.contains(n));
+
+        if contains(&["self trade", "stp"]) {
+            CancelReason::SelfTradePrevention
+        } else if contains(&["insufficient"]) {
+            CancelReason::InsufficientFunds
+        } else if contains(&["post only"]) {
+            CancelReason::PostOnly
+        } else if contains(&["expire"]) {
+            CancelReason::Expired
+        } else if contains(&["ioc", "fok", "immediate or cancel", "fill or kill"]) {
+            CancelReason::ImmediateOrCancel
+        } else if contains(&["user", "client", "requested"]) {
+            CancelReason::UserRequested
+        } else {
+            CancelReason::Other(message.to_string())
+        }
+    }
+}
+
+impl From<String> for CancelReason {
+    fn from(message: String) -> Self {
+        CancelReason::parse(&message)
+    }
+}
+
+impl From<CancelReason> for String {
+    fn from(reason: CancelReason) -> Self {
+        reason.as_ref().to_string()
+    }
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl AsRef<str> for CancelReason {
+    fn as_ref(&self) -> &str {
+        match self {
+            CancelReason::UserRequested => "USER_REQUESTED",
+            CancelReason::InsufficientFunds => "INSUFFICIENT_FUNDS",
+            CancelReason::SelfTradePrevention => "SELF_TRADE_PREVENTION",
+            CancelReason::Expired => "EXPIRED",
+            CancelReason::PostOnly => "POST_ONLY",
+            CancelReason::ImmediateOrCancel => "IMMEDIATE_OR_CANCEL",
+            CancelReason::Other(message) => message,
+        }
+    }
+}
+
+/// Reason an order was rejected, parsed from the free-text reject message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum RejectMessageReason {
+    /// Rejected due to insufficient funds.
+    InsufficientFunds,
+    /// Funds for the order could not be held.
+    HoldFailure,
+    /// Rejected since the maximum amount of open orders was reached.
+    TooManyOpenOrders,
+    /// Rejected since too many requests were made.
+    RateLimitExceeded,
+    /// Post-only order would have taken liquidity.
+    PostOnly,
+    /// Message did not match a known reason, contains the raw message.
+    Other(String),
+}
+
+impl RejectMessageReason {
+    /// Parses a reject message into a reason, falling back to `Other` containing the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Reject message received from the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::order::RejectMessageReason;
+    ///
+    /// assert_eq!(
+    ///     RejectMessageReason::parse("Insufficient balance in source account"),
+    ///     RejectMessageReason::InsufficientFunds
+    /// );
+    /// assert_eq!(
+    ///     RejectMessageReason::parse("Something new"),
+    ///     RejectMessageReason::Other("Something new".to_string())
+    /// );
+    /// ```
+    pub fn parse(message: &str) -> Self {
+        let normalized = message.to_lowercase().replace(['_', '-'], " ");
+        let contains = |needles: &[&str]| needles.iter().any(|n| normalized.contains(n));
+
+        if contains(&["insufficient"]) {
+            RejectMessageReason::InsufficientFunds
+        } else if contains(&["hold failure", "hold failed", "failed to hold"]) {
+            RejectMessageReason::HoldFailure
+        } else if contains(&["too many open orders", "maximum open orders", "max open orders"]) {
+            RejectMessageReason::TooManyOpenOrders
+        } else if contains(&["rate limit"]) {
+            RejectMessageReason::RateLimitExceeded
+        } else if contains(&["post only"]) {
+            RejectMessageReason::PostOnly
+        } else {
+            RejectMessageReason::Other(message.to_string())
+        }
+    }
+}
+
+impl From<String> for RejectMessageReason {
+    fn from(message: String) -> Self {
+        RejectMessageReason::parse(&message)
+    }
+}
+
+impl From<RejectMessageReason> for String {
+    fn from(reason: RejectMessageReason) -> Self {
+        reason.as_ref().to_string()
+    }
+}
+
+impl fmt::Display for RejectMessageReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl AsRef<str> for RejectMessageReason {
+    fn as_ref(&self) -> &str {
+        match self {
+            RejectMessageReason::InsufficientFunds => "INSUFFICIENT_FUNDS",
+            RejectMessageReason::HoldFailure => "HOLD_FAILURE",
+            RejectMessageReason::TooManyOpenOrders => "TOO_MANY_OPEN_ORDERS",
+            RejectMessageReason::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            RejectMessageReason::PostOnly => "POST_ONLY",
+            RejectMessageReason::Other(message) => message,
+        }
+    }
+}
+
+/// Reason an order edit or edit preview was rejected, parsed from the failure reason.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum EditFailureReason {
+    /// Order cannot be edited, such as when it is not an open Good 'til Cancelled limit order.
+    /// Cancelling and replacing the order is required instead.
+    NotEditable,
+    /// Order being edited could not be found.
+    OrderNotFound,
+    /// Edited price is invalid.
+    PriceInvalid,
+    /// Edited size is invalid, such as being below the size already filled.
+    SizeInvalid,
+    /// Edit does not change the order.
+    Unchanged,
+    /// Reason did not match a known reason, contains the raw reason.
+    Other(String),
+}
+
+impl EditFailureReason {
+    /// Parses a failure reason into a typed reason, falling back to `Other` containing the reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Edit or preview failure reason received from the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::order::EditFailureReason;
+    ///
+    /// assert_eq!(
+    ///     EditFailureReason::parse("ONLY_LIMIT_ORDER_EDITS_SUPPORTED"),
+    ///     EditFailureReason::NotEditable
+    /// );
+    /// assert_eq!(
+    ///     EditFailureReason::parse("PREVIEW_INVALID_LIMIT_PRICE"),
+    ///     EditFailureReason::PriceInvalid
+    /// );
+    /// assert_eq!(
+    ///     EditFailureReason::parse("SOMETHING_NEW"),
+    ///     EditFailureReason::Other("SOMETHING_NEW".to_string())
+    /// );
+    /// ```
+    pub fn parse(reason: &str) -> Self {
+        let normalized = reason.to_lowercase().replace(['_', '-'], " ");
+        let contains = |needles: &[&str]| needles.iter().any(|n| normalized.contains(n));
+
+        if contains(&["equal to original", "unchanged"]) {
+            EditFailureReason::Unchanged
+        } else if contains(&["not found"]) {
+            EditFailureReason::OrderNotFound
+        } else if contains(&[
+            "only limit",
+            "only open",
+            "not editable",
+            "cannot edit order",
+        ]) {
+            EditFailureReason::NotEditable
+        } else if contains(&["size"]) {
+            EditFailureReason::SizeInvalid
+        } else if contains(&["price"]) {
+            EditFailureReason::PriceInvalid
+        } else {
+            EditFailureReason::Other(reason.to_string())
+        }
+    }
+}
+
+impl From<String> for EditFailureReason {
+    fn from(reason: String) -> Self {
+        EditFailureReason::parse(&reason)
+    }
+}
+
+impl From<EditFailureReason> for String {
+    fn from(reason: EditFailureReason) -> Self {
+        reason.as_ref().to_string()
+    }
+}
+
+impl fmt::Display for EditFailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl AsRef<str> for EditFailureReason {
+    fn as_ref(&self) -> &str {
+        match self {
+            EditFailureReason::NotEditable => "NOT_EDITABLE",
+            EditFailureReason::OrderNotFound => "ORDER_NOT_FOUND",
+            EditFailureReason::PriceInvalid => "PRICE_INVALID",
+            EditFailureReason::SizeInvalid => "SIZE_INVALID",
+            EditFailureReason::Unchanged => "UNCHANGED",
+            EditFailureReason::Other(reason) => reason,
+        }
+    }
+}
+
 /// Enum representing the different possible order configurations.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum OrderConfiguration {
     /// Market Immediate or Cancel Order.
     #[serde(rename = "market_market_ioc")]
@@ -312,3 +613,175 @@ pub enum OrderConfiguration {
     #[serde(rename = "trigger_bracket_gtd")]
     TriggerBracketGtd(TriggerBracketGtd),
 }
+
+impl OrderConfiguration {
+    /// Size of the order in the base currency, if the configuration specifies one.
+    pub fn base_size(&self) -> Option<f64> {
+        match self {
+            OrderConfiguration::MarketIoc(config) => config.base_size,
+            OrderConfiguration::SorLimitIoc(config) => Some(config.base_size),
+            OrderConfiguration::LimitGtc(config) => config.base_size,
+            OrderConfiguration::LimitGtd(config) => config.base_size,
+            OrderConfiguration::LimitFok(config) => Some(config.base_size),
+            OrderConfiguration::StopLimitGtc(config) => Some(config.base_size),
+            OrderConfiguration::StopLimitGtd(config) => Some(config.base_size),
+            OrderConfiguration::TriggerBracketGtc(config) => Some(config.base_size),
+            OrderConfiguration::TriggerBracketGtd(config) => Some(config.base_size),
+        }
+    }
+
+    /// Size of the order in the quote currency, if the configuration specifies one.
+    pub fn quote_size(&self) -> Option<f64> {
+        match self {
+            OrderConfiguration::MarketIoc(config) => config.quote_size,
+            OrderConfiguration::LimitGtc(config) => config.quote_size,
+            OrderConfiguration::LimitGtd(config) => config.quote_size,
+            _ => None,
+        }
+    }
+
+    /// Limit price of the order, `None` for market orders.
+    pub fn limit_price(&self) -> Option<f64> {
+        match self {
+            OrderConfiguration::MarketIoc(_) => None,
+            OrderConfiguration::SorLimitIoc(config) => Some(config.limit_price),
+            OrderConfiguration::LimitGtc(config) => Some(config.limit_price),
+            OrderConfiguration::LimitGtd(config) => Some(config.limit_price),
+            OrderConfiguration::LimitFok(config) => Some(config.limit_price),
+            OrderConfiguration::StopLimitGtc(config) => Some(config.limit_price),
+            OrderConfiguration::StopLimitGtd(config) => Some(config.limit_price),
+            OrderConfiguration::TriggerBracketGtc(config) => Some(config.limit_price),
+            OrderConfiguration::TriggerBracketGtd(config) => Some(config.limit_price),
+        }
+    }
+
+    /// Notional value of the order in the quote currency. This is the quote size if one was
+    /// specified, otherwise it is the base size multiplied by the limit price.
+    /// `None` if it cannot be determined without a market price.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::order::{LimitGtc, MarketIoc, OrderConfiguration};
+    ///
+    /// let limit = OrderConfiguration::LimitGtc(LimitGtc {
+    ///     base_size: Some(0.5),
+    ///     quote_size: None,
+    ///     limit_price: 100.0,
+    ///     post_only: false,
+    /// });
+    /// assert_eq!(limit.notional(), Some(50.0));
+    ///
+    /// let market = OrderConfiguration::MarketIoc(MarketIoc {
+    ///     quote_size: None,
+    ///     base_size: Some(0.5),
+    /// });
+    /// assert_eq!(market.base_size(), Some(0.5));
+    /// assert_eq!(market.notional(), None);
+    /// ```
+    pub fn notional(&self) -> Option<f64> {
+        match self {
+            OrderConfiguration::MarketIoc(config) => config.quote_size,
+            _ => self
+                .quote_size()
+                .or_else(|| Some(self.base_size()? * self.limit_price()?)),
+        }
+    }
+}
+
+/// Formats the size of an order, preferring the quote size if one was specified.
+fn format_size(base_size: Option<f64>, quote_size: Option<f64>) -> String {
+    match (quote_size, base_size) {
+        (Some(quote_size), _) => format!("quote={}", format_decimal(quote_size)),
+        (None, Some(base_size)) => format!("base={}", format_decimal(base_size)),
+        (None, None) => "size=?".to_string(),
+    }
+}
+
+/// Concise one-line summary of the order configuration, such as for logging.
+///
+/// ```
+/// use cbadv::models::order::{LimitGtc, MarketIoc, OrderConfiguration};
+///
+/// let limit = OrderConfiguration::LimitGtc(LimitGtc {
+///     base_size: Some(0.5),
+///     quote_size: None,
+///     limit_price: 50000.0,
+///     post_only: true,
+/// });
+/// assert_eq!(limit.to_string(), "LIMIT GTC base=0.5 @ 50000 post_only=true");
+///
+/// let market = OrderConfiguration::MarketIoc(MarketIoc {
+///     quote_size: Some(100.0),
+///     base_size: None,
+/// });
+/// assert_eq!(market.to_string(), "MARKET IOC quote=100");
+/// ```
+impl fmt::Display for OrderConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderConfiguration::MarketIoc(c) => {
+                write!(f, "MARKET IOC {}", format_size(c.base_size, c.quote_size))
+            }
+            OrderConfiguration::SorLimitIoc(c) => write!(
+                f,
+                "SOR LIMIT IOC base={} @ {}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price)
+            ),
+            OrderConfiguration::LimitGtc(c) => write!(
+                f,
+                "LIMIT GTC {} @ {} post_only={}",
+                format_size(c.base_size, c.quote_size),
+                format_decimal(c.limit_price),
+                c.post_only
+            ),
+            OrderConfiguration::LimitGtd(c) => write!(
+                f,
+                "LIMIT GTD {} @ {} until {} post_only={}",
+                format_size(c.base_size, c.quote_size),
+                format_decimal(c.limit_price),
+                c.end_time,
+                c.post_only
+            ),
+            OrderConfiguration::LimitFok(c) => write!(
+                f,
+                "LIMIT FOK base={} @ {}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price)
+            ),
+            OrderConfiguration::StopLimitGtc(c) => write!(
+                f,
+                "STOP LIMIT GTC base={} @ {} stop={} {}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price),
+                format_decimal(c.stop_price),
+                c.stop_direction
+            ),
+            OrderConfiguration::StopLimitGtd(c) => write!(
+                f,
+                "STOP LIMIT GTD base={} @ {} stop={} {} until {}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price),
+                format_decimal(c.stop_price),
+                c.stop_direction,
+                c.end_time
+            ),
+            OrderConfiguration::TriggerBracketGtc(c) => write!(
+                f,
+                "BRACKET GTC base={} @ {} stop_trigger={}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price),
+                format_decimal(c.stop_trigger_price)
+            ),
+            OrderConfiguration::TriggerBracketGtd(c) => write!(
+                f,
+                "BRACKET GTD base={} @ {} stop_trigger={} until {}",
+                format_decimal(c.base_size),
+                format_decimal(c.limit_price),
+                format_decimal(c.stop_trigger_price),
+                c.end_time
+            ),
+        }
+    }
+}
@@ -1,12 +1,18 @@
+mod analysis;
+#[cfg(feature = "trade")]
 mod builders;
 mod enums;
 mod queries;
+#[cfg(feature = "trade")]
 mod requests;
 mod serde_utils;
 mod types;
 
+pub use analysis::*;
+#[cfg(feature = "trade")]
 pub use builders::*;
 pub use enums::*;
 pub use queries::*;
+#[cfg(feature = "trade")]
 pub use requests::*;
 pub use types::*;
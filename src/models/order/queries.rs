@@ -5,7 +5,7 @@
 use serde::Serialize;
 
 use crate::errors::CbError;
-use crate::models::product::ProductType;
+use crate::models::product::{check_product_ids, ProductType};
 use crate::utils::QueryBuilder;
 use crate::{traits::Query, types::CbResult};
 
@@ -40,10 +40,20 @@ pub struct OrderListQuery {
     pub cursor: Option<String>,
     // Sort results by a field, results use unstable pagination. Default is sort by creation time.
     pub sort_by: Option<OrderSortBy>,
+    /// Only orders belonging to this portfolio are returned. Default is the portfolio of the API key.
+    pub retail_portfolio_id: Option<String>,
+    /// Whether the product IDs are checked to be in the `BASE-QUOTE` format before the request
+    /// is sent. Disabled by default.
+    #[serde(skip)]
+    pub validate_product_ids: bool,
 }
 
 impl Query for OrderListQuery {
     fn check(&self) -> CbResult<()> {
+        if self.validate_product_ids {
+            check_product_ids(self.product_ids.iter().flatten())?;
+        }
+
         if let Some(product_type) = &self.product_type {
             if *product_type == ProductType::Unknown {
                 return Err(CbError::BadQuery(
@@ -86,6 +96,7 @@ impl Query for OrderListQuery {
             .push_optional("limit", &self.limit)
             .push_optional("cursor", &self.cursor)
             .push_optional("sort_by", &self.sort_by)
+            .push_optional("retail_portfolio_id", &self.retail_portfolio_id)
             .build()
     }
 }
@@ -173,6 +184,19 @@ impl OrderListQuery {
         self.sort_by = Some(sort_by);
         self
     }
+
+    /// Only orders belonging to this portfolio are returned. Default is the portfolio of the API key.
+    pub fn retail_portfolio_id(mut self, retail_portfolio_id: &str) -> Self {
+        self.retail_portfolio_id = Some(retail_portfolio_id.to_string());
+        self
+    }
+
+    /// Checks the product IDs are in the `BASE-QUOTE` format before the request is sent, catching
+    /// typos such as `BTCUSD` without a request to the API.
+    pub fn validate_product_ids(mut self, validate: bool) -> Self {
+        self.validate_product_ids = validate;
+        self
+    }
 }
 
 /// Represents parameters that are optional for List Fills API request.
@@ -3,11 +3,13 @@
 //! `order/requests` contains requests that are sent to the Order API.
 
 use serde::Serialize;
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::serde_as;
+use uuid::Uuid;
 
-use crate::{errors::CbError, traits::Request, types::CbResult};
+use crate::models::product::{Product, ProductType};
+use crate::{errors::CbError, traits::Request, types::CbResult, utils::DecimalString};
 
-use super::{OrderConfiguration, OrderSide};
+use super::{Order, OrderConfiguration, OrderSide};
 
 /// A request send to the Order API to cancel orders.
 #[derive(Serialize, Debug)]
@@ -34,7 +36,7 @@ impl OrderCancelRequest {
 }
 
 /// A request send to the Order API to create an order.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct OrderCreateRequest {
     /// Client Order ID (UUID). Skipped if creating a preview order.
     #[serde(skip_serializing_if = "str::is_empty")]
@@ -48,6 +50,15 @@ pub struct OrderCreateRequest {
     pub(crate) is_preview: bool,
     /// Configuration for the order.
     pub order_configuration: OrderConfiguration,
+    /// Whether the order may only reduce an existing position.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    /// Orders sharing a self-trade prevention ID are prevented from trading against each other.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub self_trade_prevention_id: Option<String>,
+    /// ID of a previous preview of the order, links the order to the preview.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_id: Option<String>,
 }
 
 impl Request for OrderCreateRequest {
@@ -58,6 +69,89 @@ impl Request for OrderCreateRequest {
             ));
         } else if self.product_id.is_empty() {
             return Err(CbError::BadRequest("no product ID provided".to_string()));
+        } else if self.is_preview && self.preview_id.is_some() {
+            return Err(CbError::BadRequest(
+                "preview ID cannot be provided for a preview order".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&Order> for OrderCreateRequest {
+    type Error = CbError;
+
+    /// Creates a request that places an order identical to an existing order, with a new client
+    /// order ID. The configuration can be modified before placing the order, such as to change
+    /// the limit price.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::BadParse` - If the configuration of the order is not known.
+    fn try_from(order: &Order) -> CbResult<Self> {
+        let order_configuration = order.order_configuration.clone().ok_or_else(|| {
+            CbError::BadParse(format!(
+                "order {} does not have a known configuration",
+                order.order_id
+            ))
+        })?;
+
+        Ok(Self {
+            client_order_id: Uuid::new_v4().to_string(),
+            product_id: order.product_id.clone(),
+            side: order.side,
+            is_preview: false,
+            order_configuration,
+            reduce_only: None,
+            self_trade_prevention_id: None,
+            preview_id: None,
+        })
+    }
+}
+
+impl OrderCreateRequest {
+    /// Checks that the order is valid for the product it is placed on. Limit orders sized in the
+    /// quote currency are only supported for spot products, and quote sizes must be within the
+    /// limits of the product.
+    ///
+    /// # Arguments
+    ///
+    /// * `product` - Product the order is placed on.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::BadRequest` - If the order is not valid for the product.
+    pub fn check_product(&self, product: &Product) -> CbResult<()> {
+        if self.product_id != product.product_id {
+            return Err(CbError::BadRequest(format!(
+                "order is for {} but the product is {}",
+                self.product_id, product.product_id
+            )));
+        }
+
+        let Some(quote_size) = self.order_configuration.quote_size() else {
+            return Ok(());
+        };
+
+        let is_limit = matches!(
+            self.order_configuration,
+            OrderConfiguration::LimitGtc(_) | OrderConfiguration::LimitGtd(_)
+        );
+        if is_limit && product.product_type != ProductType::Spot {
+            return Err(CbError::BadRequest(format!(
+                "{} does not support limit orders sized in the quote currency",
+                product.product_id
+            )));
+        } else if quote_size < product.quote_min_size {
+            return Err(CbError::BadRequest(format!(
+                "quote size is below the minimum of {}",
+                product.quote_min_size
+            )));
+        } else if product.quote_max_size > 0.0 && quote_size > product.quote_max_size {
+            return Err(CbError::BadRequest(format!(
+                "quote size is above the maximum of {}",
+                product.quote_max_size
+            )));
         }
         Ok(())
     }
@@ -70,10 +164,10 @@ pub struct OrderEditRequest {
     /// ID of the order to edit.
     pub order_id: String,
     /// New price for order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub price: f64,
     /// New size for order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub size: f64,
 }
 
@@ -3,49 +3,96 @@
 //! `order/types` is the module containing the structs for the different order types and configurations.
 
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
+use serde_with::{serde_as, DefaultOnError, DefaultOnNull, DisplayFromStr};
 
 use crate::models::product::ProductType;
+use crate::utils::{DecimalString, NumericOrEmpty};
 
 use super::{
-    OrderSide, OrderStatus, OrderType, RejectReason, StopDirection, TimeInForce, TriggerStatus,
+    CancelReason, EditFailureReason, OrderConfiguration, OrderSide, OrderStatus, OrderType,
+    RejectMessageReason, RejectReason, StopDirection, TimeInForce, TriggerStatus,
 };
 
 /// Buy or sell a specified quantity of an Asset at the current best available market price.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MarketIoc {
     /// Amount of quote currency to spend on order. Required for BUY orders.
-    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
     #[serde(default)]
     pub quote_size: Option<f64>,
     /// Amount of base currency to spend on order. Required for SELL orders.
-    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
     #[serde(default)]
     pub base_size: Option<f64>,
 }
 
 /// Buy or sell a specified quantity of an Asset at a specified price. The Order will only post to the Order Book if it will immediately Fill; any remaining quantity is canceled.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SorLimitIoc {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
 }
 
 /// Limit Good til Cancelled.
+///
+/// Sizes and prices are rounded to 15 significant digits when serialized, removing
+/// floating-point noise that the API would reject. Orders are sized in either the base or the
+/// quote currency, quote sizing is only supported by some products.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::order::LimitGtc;
+///
+/// let config = LimitGtc {
+///     base_size: Some(0.1 + 0.2),
+///     quote_size: None,
+///     limit_price: 100.0,
+///     post_only: false,
+/// };
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, r#"{"base_size":"0.3","limit_price":"100","post_only":false}"#);
+///
+/// let config = LimitGtc {
+///     base_size: None,
+///     quote_size: Some(500.0),
+///     limit_price: 100.0,
+///     post_only: false,
+/// };
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, r#"{"quote_size":"500","limit_price":"100","post_only":false}"#);
+/// ```
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LimitGtc {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
-    pub base_size: f64,
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_size: Option<f64>,
+    /// Amount of quote currency to spend on order, for products that support quote sizing.
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_size: Option<f64>,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// Post only limit order.
     pub post_only: bool,
@@ -53,13 +100,24 @@ pub struct LimitGtc {
 
 /// Limit Good til Time (Date).
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LimitGtd {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
-    pub base_size: f64,
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_size: Option<f64>,
+    /// Amount of quote currency to spend on order, for products that support quote sizing.
+    #[serde_as(
+        serialize_as = "Option<DecimalString>",
+        deserialize_as = "DecimalString"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_size: Option<f64>,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// Time at which the order should be cancelled if it's not filled.
     pub end_time: String,
@@ -69,28 +127,28 @@ pub struct LimitGtd {
 
 /// Buy or sell a specified quantity of an Asset at a specified price. The Order will only post to the Order Book if it is to immediately and completely Fill.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LimitFok {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
 }
 
 /// Stop Limit Good til Cancelled.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StopLimitGtc {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// Price at which the order should trigger - if stop direction is Up, then the order will trigger when the last trade price goes above this, otherwise order will trigger when last trade price goes below this price.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub stop_price: f64,
     /// Possible values: [UNKNOWN_STOP_DIRECTION, STOP_DIRECTION_STOP_UP, STOP_DIRECTION_STOP_DOWN]
     pub stop_direction: StopDirection,
@@ -98,16 +156,16 @@ pub struct StopLimitGtc {
 
 /// Stop Limit Good til Time (Date).
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StopLimitGtd {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// Price at which the order should trigger - if stop direction is Up, then the order will trigger when the last trade price goes above this, otherwise order will trigger when last trade price goes below this price.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub stop_price: f64,
     /// Time at which the order should be cancelled if it's not filled.
     pub end_time: String,
@@ -117,31 +175,31 @@ pub struct StopLimitGtd {
 
 /// A Limit Order to buy or sell a specified quantity of an Asset at a specified price, with stop limit order parameters embedded in the order. If posted, the Order will remain on the Order Book until canceled.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TriggerBracketGtc {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// The price level (in quote currency) where the position will be exited. When triggered, a stop limit order is automatically placed with a limit price 5% higher for BUYS and 5% lower for SELLS.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub stop_trigger_price: f64,
 }
 
 /// A Limit Order to buy or sell a specified quantity of an Asset at a specified price, with stop limit order parameters embedded in the order. If posted, the Order will remain on the Order Book until a certain time is reached or the Order is canceled.
 #[serde_as]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TriggerBracketGtd {
     /// Amount of base currency to spend on order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub base_size: f64,
     /// Ceiling price for which the order should get filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub limit_price: f64,
     /// The price level (in quote currency) where the position will be exited. When triggered, a stop limit order is automatically placed with a limit price 5% higher for BUYS and 5% lower for SELLS.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DecimalString")]
     pub stop_trigger_price: f64,
     /// Time at which the order should be cancelled if it's not filled.
     pub end_time: String,
@@ -152,18 +210,60 @@ pub struct TriggerBracketGtd {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EditHistory {
     /// The price associated with the edit.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub price: f64,
     /// The size associated with the edit.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub size: f64,
     /// The timestamp when the edit was accepted.
+    #[serde(default)]
     pub replace_accept_timestamp: String,
 }
 
 /// Represents an Order received from the API.
+///
+/// Numeric fields are accepted as numbers or strings, including scientific notation. Empty
+/// strings are treated as zero and integral floats are accepted for counts. A missing or null
+/// `edit_history` is treated as having no edits.
+///
+/// ```
+/// use cbadv::models::order::{Order, OrderCreateRequest};
+///
+/// let order = r#"{
+///     "order_id": "1", "client_order_id": "2", "product_id": "BTC-USD", "user_id": "3",
+///     "side": "BUY", "status": "FILLED", "time_in_force": "GOOD_UNTIL_CANCELLED",
+///     "created_time": "2024-01-01T00:00:00Z", "completion_percentage": "100",
+///     "filled_size": "1.5E-8", "average_filled_price": 50000, "fee": "",
+///     "number_of_fills": "2.0", "filled_value": 0.00075, "pending_cancel": false,
+///     "size_in_quote": false, "total_fees": "0", "size_inclusive_of_fees": false,
+///     "total_value_after_fees": "0.00075", "trigger_status": "INVALID_ORDER_TYPE",
+///     "order_type": "MARKET", "reject_reason": "REJECT_REASON_UNSPECIFIED", "settled": true,
+///     "product_type": "SPOT", "reject_message": "", "cancel_message": "",
+///     "edit_history": [{"price": "", "replace_accept_timestamp": "2024-01-01T00:00:01Z"}],
+///     "order_configuration": {"market_market_ioc": {"quote_size": "0.00075", "base_size": ""}}
+/// }"#;
+///
+/// // Orders without edits may have a null edit history.
+/// let unedited = order.replace(r#"[{"price": "", "replace_accept_timestamp": "2024-01-01T00:00:01Z"}]"#, "null");
+/// let unedited: Order = serde_json::from_str(&unedited).unwrap();
+/// assert!(unedited.edit_history.is_empty());
+///
+/// let order: Order = serde_json::from_str(order).unwrap();
+/// assert_eq!(order.filled_size, 1.5e-8);
+/// assert_eq!(order.average_filled_price, 50000.0);
+/// assert_eq!(order.fee, 0.0);
+/// assert_eq!(order.number_of_fills, 2);
+/// assert_eq!(order.edit_history[0].price, 0.0);
+/// assert_eq!(order.edit_history[0].size, 0.0);
+///
+/// // Create a request to place the same order again.
+/// let request = OrderCreateRequest::try_from(&order).unwrap();
+/// assert_eq!(request.order_configuration.quote_size(), Some(0.00075));
+/// assert_eq!(request.order_configuration.base_size(), None);
+/// assert_ne!(request.client_order_id, order.client_order_id);
+/// ```
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Order {
@@ -184,27 +284,27 @@ pub struct Order {
     /// Timestamp for when the order was created.
     pub created_time: String,
     /// The percent of total order amount that has been filled.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub completion_percentage: f64,
     /// The portion (in base currency) of total order amount that has been filled.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub filled_size: f64,
     /// The average of all prices of fills for this order.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub average_filled_price: f64,
     /// Commission amount.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub fee: f64,
     /// Number of fills that have been posted for this order.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub number_of_fills: u32,
     /// The portion (in quote current) of total order amount that has been filled.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub filled_value: f64,
     /// Whether a cancel request has been initiated for the order, and not yet completed.
@@ -212,13 +312,17 @@ pub struct Order {
     /// Whether the order was placed with quote currency/
     pub size_in_quote: bool,
     /// The total fees for the order.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub total_fees: f64,
     /// Whether the order size includes fees.
     pub size_inclusive_of_fees: bool,
+    /// Amount of funds still on hold for the order, in the currency being spent.
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
+    #[serde(default)]
+    pub outstanding_hold_amount: f64,
     /// Derived field: filled_value + total_fees for buy orders and filled_value - total_fees for sell orders.
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub total_value_after_fees: f64,
     /// Possible values: \[UNKNOWN_TRIGGER_STATUS, INVALID_ORDER_TYPE, STOP_PENDING, STOP_TRIGGERED\]
@@ -236,7 +340,41 @@ pub struct Order {
     /// Message stating why the order was canceled.
     pub cancel_message: String,
     /// An array of the latest 5 edits per order.
+    #[serde_as(as = "DefaultOnNull")]
+    #[serde(default)]
     pub edit_history: Vec<EditHistory>,
+    /// Configuration the order was placed with, `None` if it is not recognized.
+    #[serde_as(as = "DefaultOnError")]
+    #[serde(default)]
+    pub order_configuration: Option<OrderConfiguration>,
+}
+
+impl Order {
+    /// Whether the stop price of a stop order has been reached, placing the order on the book.
+    /// Always `false` for orders without a stop.
+    pub fn is_stop_triggered(&self) -> bool {
+        self.trigger_status == TriggerStatus::StopTriggered
+    }
+
+    /// Typed reason the order was cancelled, parsed from `cancel_message`. `None` if there is no
+    /// cancel message.
+    pub fn cancel_reason(&self) -> Option<CancelReason> {
+        if self.cancel_message.is_empty() {
+            None
+        } else {
+            Some(CancelReason::parse(&self.cancel_message))
+        }
+    }
+
+    /// Typed reason for the order being rejected, parsed from `reject_message`. `None` if there
+    /// is no reject message.
+    pub fn reject_message_reason(&self) -> Option<RejectMessageReason> {
+        if self.reject_message.is_empty() {
+            None
+        } else {
+            Some(RejectMessageReason::parse(&self.reject_message))
+        }
+    }
 }
 
 /// Represents a fill received from the API.
@@ -255,13 +393,13 @@ pub struct Fill {
     /// Adjusted fills have possible values `REVERSAL`, `CORRECTION`, `SYNTHETIC`.
     pub trade_type: String,
     /// Price the fill was posted at.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub price: f64,
     /// Amount of order that was transacted at this fill.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub size: f64,
     /// Fee amount for fill.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub commission: f64,
     /// The product this order was created for.
@@ -278,6 +416,17 @@ pub struct Fill {
     pub side: OrderSide,
 }
 
+impl Fill {
+    /// Size of the fill in the base currency, converting from quote if required.
+    pub fn base_size(&self) -> f64 {
+        if self.size_in_quote && self.price != 0.0 {
+            self.size / self.price
+        } else {
+            self.size
+        }
+    }
+}
+
 /// Represents a list of orders received from the API.
 #[derive(Deserialize, Debug)]
 pub struct PaginatedOrders {
@@ -361,13 +510,27 @@ pub struct OrderEditResponse {
     pub errors: Vec<OrderEditError>,
 }
 
-/// Errors associated with the changes.
+/// Errors associated with the changes. Reasons are typed, allowing a rejected edit to fall back
+/// to cancelling and replacing the order when it is not editable.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::order::{EditFailureReason, OrderEditError};
+///
+/// let json = r#"{"edit_failure_reason": "ONLY_LIMIT_ORDER_EDITS_SUPPORTED"}"#;
+/// let error: OrderEditError = serde_json::from_str(json).unwrap();
+/// assert_eq!(error.edit_failure_reason, Some(EditFailureReason::NotEditable));
+/// assert!(error.preview_failure_reason.is_none());
+/// ```
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct OrderEditError {
     /// Reason the edit failed.
-    pub edit_failure_reason: Option<String>,
+    #[serde(default)]
+    pub edit_failure_reason: Option<EditFailureReason>,
     /// Reason the preview failed.
-    pub preview_failure_reason: Option<String>,
+    #[serde(default)]
+    pub preview_failure_reason: Option<EditFailureReason>,
 }
 
 /// Response from a preview edit order.
@@ -377,102 +540,163 @@ pub struct OrderEditPreview {
     /// Contains reasons for failure in the edit or preview edit operation.
     pub errors: Vec<OrderEditError>,
     /// The amount of slippage in the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub slippage: f64,
     /// The total value of the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub order_total: f64,
     /// The total commission for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub commission_total: f64,
     /// The size of the quote currency in the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub quote_size: f64,
     /// The size of the base currency in the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub base_size: f64,
     /// The best bid price at the time of the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub best_bid: f64,
     /// The best ask price at the time of the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub best_ask: f64,
     /// The average price at which the order was filled.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     #[serde(default)]
     pub average_filled_price: f64,
 }
 
 /// Represents the response for a preview of creating an order.
+///
+/// Margin fields are omitted or empty for non-margin orders, such as spot stop-limit and
+/// bracket previews. Numeric fields default to 0.0 when absent or unparsable.
+///
+/// # Example
+///
+/// ```rust
+/// use cbadv::models::order::OrderCreatePreview;
+///
+/// // Stop-limit preview, margin fields are omitted.
+/// let stop_limit = r#"{
+///     "order_total": "101.5",
+///     "commission_total": "1.5",
+///     "errs": [],
+///     "warning": [],
+///     "quote_size": "100",
+///     "base_size": "0.001",
+///     "best_bid": "99990.01",
+///     "best_ask": "100000.00",
+///     "is_max": false,
+///     "slippage": "",
+///     "preview_id": "b40bbff9-17ce-4726-8b64-9de7ae57ad26"
+/// }"#;
+/// let preview: OrderCreatePreview = serde_json::from_str(stop_limit).unwrap();
+/// assert_eq!(preview.base_size, 0.001);
+/// assert_eq!(preview.slippage, 0.0);
+/// assert!(!preview.is_below_minimum());
+/// assert_eq!(preview.leverage, 0.0);
+/// assert_eq!(preview.max_leverage, None);
+///
+/// // Bracket preview, margin fields are present but empty.
+/// let bracket = r#"{
+///     "order_total": "50.25",
+///     "commission_total": "0.25",
+///     "errs": [],
+///     "warning": [],
+///     "quote_size": "50",
+///     "base_size": "",
+///     "best_bid": "99990.01",
+///     "best_ask": "100000.00",
+///     "is_max": false,
+///     "order_margin_total": "",
+///     "leverage": "",
+///     "long_leverage": "",
+///     "short_leverage": "",
+///     "slippage": "0",
+///     "preview_id": "9b1a6a36-3f0a-4bd8-a9e8-6a1e3b3e0d61",
+///     "current_liquidation_buffer": "",
+///     "projected_liquidation_buffer": "",
+///     "max_leverage": ""
+/// }"#;
+/// let preview: OrderCreatePreview = serde_json::from_str(bracket).unwrap();
+/// assert_eq!(preview.order_total, 50.25);
+/// assert_eq!(preview.base_size, 0.0);
+/// assert_eq!(preview.max_leverage, None);
+/// ```
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderCreatePreview {
     /// The total value of the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub order_total: f64,
     /// The total commission for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub commission_total: f64,
     /// List of errors encountered during the preview.
+    #[serde(default)]
     pub errs: Vec<String>,
     /// List of warnings related to the order preview.
+    #[serde(default)]
     pub warning: Vec<String>,
     /// The best bid price at the time of the preview.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub best_bid: f64,
     /// The best ask price at the time of the preview.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub best_ask: f64,
     /// The size of the quote currency in the order.
     /// NOTE: There were issues deserializing this in the past.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub quote_size: f64,
     /// The size of the base currency in the order.
     /// NOTE: There were issues deserializing this in the past.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
     pub base_size: f64,
     /// Indicates whether the maximum allowed amount was used.
+    #[serde(default)]
     pub is_max: bool,
     /// The total margin required for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub order_margin_total: f64,
     /// The leverage applied to the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub leverage: f64,
     /// The long leverage available for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub long_leverage: f64,
     /// The short leverage available for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub short_leverage: f64,
     /// The projected slippage for the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub slippage: f64,
     /// The unique identifier for the order preview.
+    #[serde(default)]
     pub preview_id: String,
     /// The current liquidation buffer for the account.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub current_liquidation_buffer: f64,
     /// The projected liquidation buffer after the order.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
     #[serde(default)]
     pub projected_liquidation_buffer: f64,
     /// The maximum leverage available for the order.
@@ -481,6 +705,18 @@ pub struct OrderCreatePreview {
     pub max_leverage: Option<f64>,
 }
 
+impl OrderCreatePreview {
+    /// Checks if the preview reported that the order is below the minimum size of the product,
+    /// such as `PREVIEW_INVALID_BASE_SIZE_TOO_SMALL`. The order can be raised to the minimum with
+    /// `OrderCreateBuilder::bump_to_minimum`.
+    pub fn is_below_minimum(&self) -> bool {
+        self.errs
+            .iter()
+            .chain(&self.warning)
+            .any(|reason| reason.contains("TOO_SMALL") || reason.contains("BELOW_MIN"))
+    }
+}
+
 /// Represents a cancel order response from the API.
 #[derive(Deserialize, Debug)]
 pub(crate) struct OrderCancelWrapper {
@@ -8,14 +8,16 @@ use core::fmt;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
 
-use super::shared::Balance;
+use super::shared::{Balance, ExchangeRates};
 use crate::errors::CbError;
-use crate::traits::{Query, Request};
+use crate::traits::Query;
+#[cfg(feature = "trade")]
+use crate::traits::Request;
 use crate::types::CbResult;
 use crate::utils::QueryBuilder;
 
 /// Portfolio type for a user's portfolio.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PortfolioType {
     /// Portfolio type for a user's default portfolio.
@@ -46,7 +48,7 @@ impl fmt::Display for PortfolioType {
 }
 
 /// Enum for `PositionSide` values.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PositionSide {
     #[serde(rename = "FUTURES_POSITION_SIDE_UNSPECIFIED")]
     Unspecified,
@@ -57,7 +59,7 @@ pub enum PositionSide {
 }
 
 /// Enum for `MarginType` values.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MarginType {
     #[serde(rename = "MARGIN_TYPE_UNSPECIFIED")]
@@ -98,6 +100,58 @@ pub struct PortfolioBalances {
     pub perp_unrealized_pnl: Balance,
 }
 
+impl PortfolioBalances {
+    /// Sums the futures, cash equivalent, and crypto balances. Returns `None` if any of the
+    /// categories are not denominated in the currency provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `currency` - Currency the balances are expected to be denominated in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::portfolio::PortfolioBalances;
+    ///
+    /// let balances: PortfolioBalances = serde_json::from_str(r#"{
+    ///     "total_balance": {"value": "175", "currency": "USD"},
+    ///     "total_futures_balance": {"value": "25", "currency": "USD"},
+    ///     "total_cash_equivalent_balance": {"value": "100", "currency": "USD"},
+    ///     "total_crypto_balance": {"value": "50", "currency": "USD"},
+    ///     "futures_unrealized_pnl": {"value": "-5", "currency": "USD"},
+    ///     "perp_unrealized_pnl": {"value": "2.5", "currency": "USD"}
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(balances.total_value("USD"), Some(175.0));
+    /// assert_eq!(balances.total_value("EUR"), None);
+    /// assert_eq!(balances.net_liquidation_value(), Some(172.5));
+    /// ```
+    pub fn total_value(&self, currency: &str) -> Option<f64> {
+        [
+            &self.total_futures_balance,
+            &self.total_cash_equivalent_balance,
+            &self.total_crypto_balance,
+        ]
+        .iter()
+        .try_fold(0.0, |total, balance| {
+            (balance.currency == currency).then_some(total + balance.value)
+        })
+    }
+
+    /// Value of the portfolio if all positions were closed, the total value of the categories
+    /// adjusted by the unrealized profit and loss of futures and perpetuals. Returns `None` if the
+    /// balances are not all denominated in the same currency.
+    pub fn net_liquidation_value(&self) -> Option<f64> {
+        let currency = &self.total_balance.currency;
+        let total = self.total_value(currency)?;
+        [&self.futures_unrealized_pnl, &self.perp_unrealized_pnl]
+            .iter()
+            .try_fold(total, |total, pnl| {
+                (pnl.currency == *currency).then_some(total + pnl.value)
+            })
+    }
+}
+
 /// Spot position details.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -139,6 +193,7 @@ pub struct MonetaryDetails {
 }
 
 /// Perpetual position details.
+#[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PerpPosition {
     /// The product ID associated with the perpetual position.
@@ -160,7 +215,10 @@ pub struct PerpPosition {
     /// Size of sell orders in the position.
     pub sell_order_size: f64,
     /// Initial margin contribution for the position.
-    pub im_contribution: String,
+    /// NOTE: Defaults to 0.0 if empty, such as for a flat position.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub im_contribution: f64,
     /// Unrealized profit and loss for the position.
     pub unrealized_pnl: MonetaryDetails,
     /// The mark price of the position.
@@ -168,7 +226,10 @@ pub struct PerpPosition {
     /// The liquidation price of the position.
     pub liquidation_price: MonetaryDetails,
     /// Leverage used in the position.
-    pub leverage: String,
+    /// NOTE: Defaults to 0.0 if empty, such as for a flat position.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub leverage: f64,
     /// Initial margin notional value.
     pub im_notional: MonetaryDetails,
     /// Maintenance margin notional value.
@@ -178,11 +239,35 @@ pub struct PerpPosition {
     /// The margin type for the position (e.g., cross, isolated).
     pub margin_type: MarginType,
     /// The liquidation buffer for the position.
-    pub liquidation_buffer: String,
+    /// NOTE: Defaults to 0.0 if empty, such as for a flat position.
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
+    pub liquidation_buffer: f64,
     /// The liquidation percentage for the position.
     pub liquidation_percentage: f64,
 }
 
+impl PerpPosition {
+    /// Unrealized profit and loss of the position valued at the mark price provided instead of
+    /// the mark price reported by the API. The entry price is the VWAP in the raw currency, so the
+    /// mark price is expected to be in the same currency. Long positions profit when the mark is
+    /// above the entry price and short positions profit when it is below, if the side is
+    /// unspecified it is taken from the sign of the net size.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark` - Price to value the position at.
+    pub fn unrealized_pnl_at(&self, mark: f64) -> f64 {
+        let direction = match self.position_side {
+            PositionSide::Long => 1.0,
+            PositionSide::Short => -1.0,
+            PositionSide::Unspecified => self.net_size.signum(),
+        };
+
+        (mark - self.vwap.raw_currency.value) * self.net_size.abs() * direction
+    }
+}
+
 /// Futures position details.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FuturesPosition {
@@ -214,7 +299,31 @@ pub struct FuturesPosition {
     pub notional_value: String,
 }
 
-/// Represents the breakdown of the portfolio returned by the API.
+/// Represents the breakdown of the portfolio returned by the API. Position arrays that are
+/// omitted, such as the perpetual and futures positions of a spot-only account, are empty.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::portfolio::PortfolioBreakdown;
+///
+/// let json = r#"{
+///     "portfolio": {"name": "Default", "uuid": "abc", "type": "DEFAULT", "deleted": false},
+///     "portfolio_balances": {
+///         "total_balance": {"value": "100", "currency": "USD"},
+///         "total_futures_balance": {"value": "0", "currency": "USD"},
+///         "total_cash_equivalent_balance": {"value": "100", "currency": "USD"},
+///         "total_crypto_balance": {"value": "0", "currency": "USD"},
+///         "futures_unrealized_pnl": {"value": "0", "currency": "USD"},
+///         "perp_unrealized_pnl": {"value": "0", "currency": "USD"}
+///     },
+///     "spot_positions": []
+/// }"#;
+///
+/// let breakdown: PortfolioBreakdown = serde_json::from_str(json).unwrap();
+/// assert!(breakdown.perp_positions.is_empty());
+/// assert!(breakdown.futures_positions.is_empty());
+/// ```
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PortfolioBreakdown {
     /// The portfolio associated with the breakdown.
@@ -222,13 +331,126 @@ pub struct PortfolioBreakdown {
     /// Balances across different portfolio categories.
     pub portfolio_balances: PortfolioBalances,
     /// Spot positions held in the portfolio.
+    #[serde(default)]
     pub spot_positions: Vec<SpotPosition>,
     /// Perpetual positions held in the portfolio.
+    #[serde(default)]
     pub perp_positions: Vec<PerpPosition>,
     /// Futures positions held in the portfolio.
+    #[serde(default)]
     pub futures_positions: Vec<FuturesPosition>,
 }
 
+impl PortfolioBreakdown {
+    /// Effective leverage of the account, the total notional of the perpetual and futures
+    /// positions divided by the total balance. Perpetual notionals are taken in the user's native
+    /// currency and futures notionals are assumed to be in the currency of the total balance.
+    /// Returns `None` if the total balance is not positive, a perpetual notional is in a different
+    /// currency than the total balance, or a futures notional cannot be parsed.
+    pub fn account_leverage(&self) -> Option<f64> {
+        let equity = &self.portfolio_balances.total_balance;
+        if equity.value <= 0.0 {
+            return None;
+        }
+
+        let perp_notional = self
+            .perp_positions
+            .iter()
+            .try_fold(0.0, |total, position| {
+                let notional = &position.position_notional.user_native_currency;
+                (notional.currency == equity.currency).then_some(total + notional.value.abs())
+            })?;
+
+        let futures_notional = self
+            .futures_positions
+            .iter()
+            .try_fold(0.0, |total, position| {
+                let notional = position.notional_value.parse::<f64>().ok()?;
+                Some(total + notional.abs())
+            })?;
+
+        Some((perp_notional + futures_notional) / equity.value)
+    }
+
+    /// Re-denominates the breakdown into another currency using a single snapshot of exchange
+    /// rates, avoiding a refetch per currency. Converts the portfolio balances, the spot cost
+    /// basis and fiat amounts, and the user native values of the perpetual positions. Raw
+    /// perpetual values and futures positions keep the currency of their contracts. Returns
+    /// `None` if a required rate is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `rates` - Exchange rates used for every conversion.
+    /// * `currency` - Currency to convert the breakdown to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use cbadv::models::portfolio::PortfolioBreakdown;
+    /// use cbadv::models::shared::ExchangeRates;
+    ///
+    /// let json = r#"{
+    ///     "portfolio": {"name": "Default", "uuid": "abc", "type": "DEFAULT", "deleted": false},
+    ///     "portfolio_balances": {
+    ///         "total_balance": {"value": "100", "currency": "USD"},
+    ///         "total_futures_balance": {"value": "0", "currency": "USD"},
+    ///         "total_cash_equivalent_balance": {"value": "100", "currency": "USD"},
+    ///         "total_crypto_balance": {"value": "0", "currency": "USD"},
+    ///         "futures_unrealized_pnl": {"value": "0", "currency": "USD"},
+    ///         "perp_unrealized_pnl": {"value": "0", "currency": "USD"}
+    ///     }
+    /// }"#;
+    /// let breakdown: PortfolioBreakdown = serde_json::from_str(json).unwrap();
+    ///
+    /// let rates = ExchangeRates::new("USD".to_string(), HashMap::from([("EUR".to_string(), 0.5)]));
+    /// let eur = breakdown.in_currency(&rates, "EUR").unwrap();
+    /// assert_eq!(eur.portfolio_balances.total_balance.value, 50.0);
+    /// assert_eq!(eur.portfolio_balances.total_balance.currency, "EUR");
+    /// assert!(breakdown.in_currency(&rates, "JPY").is_none());
+    /// ```
+    pub fn in_currency(&self, rates: &ExchangeRates, currency: &str) -> Option<PortfolioBreakdown> {
+        let mut breakdown = self.clone();
+
+        let balances = &mut breakdown.portfolio_balances;
+        let fiat_rate = rates.rate(&balances.total_balance.currency, currency)?;
+        for balance in [
+            &mut balances.total_balance,
+            &mut balances.total_futures_balance,
+            &mut balances.total_cash_equivalent_balance,
+            &mut balances.total_crypto_balance,
+            &mut balances.futures_unrealized_pnl,
+            &mut balances.perp_unrealized_pnl,
+        ] {
+            *balance = rates.convert(balance, currency)?;
+        }
+
+        for position in &mut breakdown.spot_positions {
+            position.total_balance_fiat *= fiat_rate;
+            position.available_to_trade_fiat *= fiat_rate;
+            position.cost_basis = rates.convert(&position.cost_basis, currency)?;
+        }
+
+        for position in &mut breakdown.perp_positions {
+            for details in [
+                &mut position.vwap,
+                &mut position.unrealized_pnl,
+                &mut position.mark_price,
+                &mut position.liquidation_price,
+                &mut position.im_notional,
+                &mut position.mm_notional,
+                &mut position.position_notional,
+            ] {
+                details.user_native_currency =
+                    rates.convert(&details.user_native_currency, currency)?;
+            }
+        }
+
+        Some(breakdown)
+    }
+}
+
 /// Create or Edit an existing portfolio.
 #[derive(Serialize, Default, Debug)]
 pub struct PortfolioModifyRequest {
@@ -236,6 +458,7 @@ pub struct PortfolioModifyRequest {
     pub name: String,
 }
 
+#[cfg(feature = "trade")]
 impl Request for PortfolioModifyRequest {
     fn check(&self) -> CbResult<()> {
         if self.name.is_empty() {
@@ -267,6 +490,7 @@ pub struct PortfolioMoveFundsRequest {
     pub target_portfolio_uuid: String,
 }
 
+#[cfg(feature = "trade")]
 impl Request for PortfolioMoveFundsRequest {
     fn check(&self) -> CbResult<()> {
         if self.funds.value <= 0.0 {
@@ -5,21 +5,24 @@
 //! Currency information, Product Book, and Best Bids and Asks for multiple products.
 
 use core::fmt;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
 
 use crate::constants::products::CANDLE_MAXIMUM;
 use crate::errors::CbError;
+#[cfg(feature = "websocket")]
 use crate::models::websocket::CandleUpdate;
 use crate::time::{self, Granularity};
 use crate::traits::Query;
 use crate::types::CbResult;
-use crate::utils::QueryBuilder;
+use crate::utils::{format_decimal, NumericOrEmpty, QueryBuilder};
 
 use super::order::OrderSide;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProductType {
     /// Unknown product type.
@@ -48,7 +51,7 @@ impl AsRef<str> for ProductType {
 }
 
 /// Represents the trading session state.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SessionState {
     #[serde(rename = "FCM_TRADING_SESSION_STATE_UNDEFINED")]
     Undefined,
@@ -63,7 +66,7 @@ pub enum SessionState {
 }
 
 /// Reasons for a trading session to close.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CloseReason {
     #[serde(rename = "FCM_TRADING_SESSION_CLOSED_REASON_UNDEFINED")]
     Undefined,
@@ -75,14 +78,37 @@ pub enum CloseReason {
     VendorMaintenance,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Venue the product is traded on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProductVenue {
-    #[serde(rename = "UNKNOWN_VENUE_TYPE")]
-    Unknown,
+    /// Coinbase Exchange, spot products.
     Cbe,
+    /// Futures Commission Merchant, US derivatives.
     Fcm,
+    /// International Exchange, perpetuals.
     Intx,
+    /// Unknown venue, also used for venues that are not yet supported.
+    #[serde(rename = "UNKNOWN_VENUE_TYPE", other)]
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for ProductVenue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl AsRef<str> for ProductVenue {
+    fn as_ref(&self) -> &str {
+        match self {
+            ProductVenue::Unknown => "UNKNOWN_VENUE_TYPE",
+            ProductVenue::Cbe => "CBE",
+            ProductVenue::Fcm => "FCM",
+            ProductVenue::Intx => "INTX",
+        }
+    }
 }
 
 /// Fcm specific scheduled maintenance details.
@@ -163,13 +189,16 @@ pub struct Product {
     #[serde_as(as = "DisplayFromStr")]
     pub price: f64,
     /// The amount the price of the product has changed, in percent, in the last 24 hours.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
     pub price_percentage_change_24h: f64,
     /// The trading volume for the product in the last 24 hours.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
     pub volume_24h: f64,
     /// The percentage amount the volume of the product has changed in the last 24 hours.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde(default)]
     pub volume_percentage_change_24h: f64,
     /// Minimum amount base value can be increased or decreased at once.
     #[serde_as(as = "DisplayFromStr")]
@@ -237,6 +266,7 @@ pub struct Product {
     /// Display name of the product.
     pub display_name: String,
     /// The sole venue id for the product. Defaults to CBE if the product is not specific to a single venue
+    #[serde(default)]
     pub product_venue: ProductVenue,
     /// Approximate 24-hour trading volume in quote currency.
     #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
@@ -246,15 +276,350 @@ pub struct Product {
     pub future_product_details: Option<FutureDetails>,
 }
 
+impl Product {
+    /// Current midpoint of the bid-ask spread parsed from `mid_market_price`, `None` if it is
+    /// not populated or is not a valid number.
+    pub fn mid_price(&self) -> Option<f64> {
+        self.mid_market_price
+            .parse::<f64>()
+            .ok()
+            .filter(|price| price.is_finite() && *price > 0.0)
+    }
+
+    /// Checks if new orders can currently be placed for the product. The product must be online
+    /// and not disabled, cancel only, or view only. Futures must also be within an open trading
+    /// session. Products in limit only mode are considered tradeable, only market orders are
+    /// rejected.
+    pub fn is_tradeable_now(&self) -> bool {
+        let session_open = self
+            .fcm_trading_session_details
+            .as_ref()
+            .is_none_or(|session| {
+                session.is_session_open && session.session_state != SessionState::Close
+            });
+
+        session_open
+            && self.status.eq_ignore_ascii_case("online")
+            && !self.is_disabled
+            && !self.trading_disabled
+            && !self.cancel_only
+            && !self.view_only
+    }
+
+    /// Obtains the next time in UNIX format (seconds) the trading session changes, either opening,
+    /// closing, or entering or leaving maintenance. `None` if the product has no trading session,
+    /// such as spot products, or no upcoming change is known.
+    pub fn next_session_change(&self) -> Option<u64> {
+        let session = self.fcm_trading_session_details.as_ref()?;
+        let now = time::now();
+
+        let mut changes = vec![&session.open_time, &session.close_time];
+        if let Some(maintenance) = &session.maintenance {
+            changes.push(&maintenance.start);
+            changes.push(&maintenance.end);
+        }
+
+        changes
+            .into_iter()
+            .filter_map(|timestamp| time::from_rfc3339(timestamp).ok())
+            .filter(|timestamp| *timestamp > now)
+            .min()
+    }
+
+    /// Venue the product is traded on. If the venue is unknown, it is inferred from the product
+    /// type: spot products are CBE, perpetual futures are INTX, and other futures are FCM.
+    pub fn venue(&self) -> ProductVenue {
+        match (&self.product_venue, &self.product_type) {
+            (ProductVenue::Unknown, ProductType::Spot) => ProductVenue::Cbe,
+            (ProductVenue::Unknown, ProductType::Future) => {
+                let is_perpetual = self
+                    .future_product_details
+                    .as_ref()
+                    .is_some_and(|details| details.perpetual_details.is_some());
+                if is_perpetual {
+                    ProductVenue::Intx
+                } else {
+                    ProductVenue::Fcm
+                }
+            }
+            (venue, _) => venue.clone(),
+        }
+    }
+
+    /// Consolidated 24 hour statistics for the product, values that are absent default to 0.
+    pub fn stats_24h(&self) -> Stats24h {
+        let value = |value: f64| if value.is_finite() { value } else { 0.0 };
+        Stats24h {
+            price_percentage_change: value(self.price_percentage_change_24h),
+            volume: value(self.volume_24h),
+            volume_percentage_change: value(self.volume_percentage_change_24h),
+            approximate_quote_volume: value(self.approximate_quote_24h_volume),
+        }
+    }
+
+    /// Aligns the price to the price increment of the product and formats it with the precision
+    /// of the increment, ready to be sent to the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price to align, in quote currency.
+    /// * `mode` - Direction to round the price in.
+    pub fn format_price(&self, price: f64, mode: RoundingMode) -> String {
+        format_aligned(price, self.price_increment, mode)
+    }
+
+    /// Aligns the size to the base increment of the product and formats it with the precision
+    /// of the increment, ready to be sent to the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Size to align, in base currency.
+    /// * `mode` - Direction to round the size in.
+    pub fn format_size(&self, size: f64, mode: RoundingMode) -> String {
+        format_aligned(size, self.base_increment, mode)
+    }
+}
+
+/// Direction a value is rounded in when it is aligned to an increment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Rounds to the nearest increment, halfway values are rounded away from zero.
+    Nearest,
+    /// Rounds toward zero, such as for sizes to avoid spending more than intended.
+    TowardZero,
+    /// Rounds away from zero.
+    AwayFromZero,
+    /// Rounds a price so it does not cross the spread, down for buys and up for sells. Rounds to
+    /// the nearest increment if the side is unknown.
+    ForSide(OrderSide),
+}
+
+/// Aligns a value to a multiple of the increment, rounding in the direction of the mode. The
+/// value is returned unchanged if the increment is not positive.
+///
+/// # Arguments
+///
+/// * `value` - Value to align.
+/// * `increment` - Increment the value must be a multiple of, such as the price increment.
+/// * `mode` - Direction to round the value in.
+///
+/// ```
+/// use cbadv::models::order::OrderSide;
+/// use cbadv::models::product::{align_to, RoundingMode};
+///
+/// assert_eq!(align_to(100.26, 0.1, RoundingMode::Nearest), 100.3);
+/// assert_eq!(align_to(100.26, 0.1, RoundingMode::TowardZero), 100.2);
+/// assert_eq!(align_to(-100.26, 0.1, RoundingMode::AwayFromZero), -100.3);
+/// assert_eq!(align_to(100.26, 0.1, RoundingMode::ForSide(OrderSide::Buy)), 100.2);
+/// assert_eq!(align_to(100.21, 0.1, RoundingMode::ForSide(OrderSide::Sell)), 100.3);
+/// // Values already aligned are unchanged despite floating-point noise.
+/// assert_eq!(align_to(0.3, 0.1, RoundingMode::AwayFromZero), 0.3);
+/// ```
+pub fn align_to(value: f64, increment: f64, mode: RoundingMode) -> f64 {
+    if !value.is_finite() || !increment.is_finite() || increment <= 0.0 {
+        return value;
+    }
+
+    // Remove floating-point noise so aligned values are not moved by an increment.
+    let steps = format_decimal(value / increment)
+        .parse::<f64>()
+        .unwrap_or(value / increment);
+    let steps = match mode {
+        RoundingMode::Nearest | RoundingMode::ForSide(OrderSide::Unknown) => steps.round(),
+        RoundingMode::TowardZero => steps.trunc(),
+        RoundingMode::AwayFromZero => steps.signum() * steps.abs().ceil(),
+        RoundingMode::ForSide(OrderSide::Buy) => steps.floor(),
+        RoundingMode::ForSide(OrderSide::Sell) => steps.ceil(),
+    };
+
+    let precision = increment_precision(increment);
+    format!("{:.precision$}", steps * increment)
+        .parse::<f64>()
+        .unwrap_or(steps * increment)
+}
+
+/// Aligns a value to the increment and formats it with the precision of the increment.
+fn format_aligned(value: f64, increment: f64, mode: RoundingMode) -> String {
+    let aligned = align_to(value, increment, mode);
+    if increment.is_finite() && increment > 0.0 {
+        format!("{:.*}", increment_precision(increment), aligned)
+    } else {
+        format_decimal(aligned)
+    }
+}
+
+/// Amount of decimal places needed to represent the increment.
+fn increment_precision(increment: f64) -> usize {
+    format_decimal(increment)
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// Represents the 24 hour statistics for a product.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats24h {
+    /// The amount the price of the product has changed, in percent.
+    pub price_percentage_change: f64,
+    /// The trading volume for the product, in base currency.
+    pub volume: f64,
+    /// The percentage amount the volume of the product has changed.
+    pub volume_percentage_change: f64,
+    /// Approximate trading volume, in quote currency.
+    pub approximate_quote_volume: f64,
+}
+
+/// Checks that a product ID is in the `BASE-QUOTE` format, such as `BTC-USD`. Additional segments
+/// are allowed for futures and perpetuals, such as `BIT-31JAN25-CDE` and `BTC-PERP-INTX`. Only the
+/// format is checked, not whether the product exists.
+///
+/// # Arguments
+///
+/// * `product_id` - Product ID to check.
+///
+/// ```
+/// use cbadv::models::product::is_valid_product_id;
+///
+/// assert!(is_valid_product_id("BTC-USD"));
+/// assert!(is_valid_product_id("BTC-PERP-INTX"));
+/// assert!(!is_valid_product_id("BTCUSD"));
+/// assert!(!is_valid_product_id("BTC-"));
+/// assert!(!is_valid_product_id("BTC USD"));
+/// ```
+pub fn is_valid_product_id(product_id: &str) -> bool {
+    let mut segments = 0;
+    for segment in product_id.split('-') {
+        if segment.is_empty() || !segment.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return false;
+        }
+        segments += 1;
+    }
+
+    segments >= 2
+}
+
+/// Checks that every product ID is in a valid format.
+///
+/// # Errors
+///
+/// * `CbError::BadQuery` - If a product ID is not in the `BASE-QUOTE` format.
+pub(crate) fn check_product_ids<'a>(
+    product_ids: impl IntoIterator<Item = &'a String>,
+) -> CbResult<()> {
+    match product_ids
+        .into_iter()
+        .find(|product_id| !is_valid_product_id(product_id))
+    {
+        Some(product_id) => Err(CbError::BadQuery(format!(
+            "product_id '{product_id}' is not in the BASE-QUOTE format"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Collection of products indexed by their product ID, such as from `ProductApi::get_all`.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    /// Products in the catalog. [key: Product Id, value: Product]
+    products: HashMap<String, Product>,
+}
+
+impl From<Vec<Product>> for Catalog {
+    fn from(products: Vec<Product>) -> Self {
+        Self {
+            products: products
+                .into_iter()
+                .map(|product| (product.product_id.clone(), product))
+                .collect(),
+        }
+    }
+}
+
+impl Catalog {
+    /// Creates a new catalog from the products provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `products` - Products to add to the catalog.
+    pub fn new(products: Vec<Product>) -> Self {
+        Self::from(products)
+    }
+
+    /// Obtains a product by its product ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID of the product to obtain.
+    pub fn get(&self, product_id: &str) -> Option<&Product> {
+        self.products.get(product_id)
+    }
+
+    /// Resolves a product to the product of its unified book by following the `alias` of each
+    /// product until a product without an alias is reached, such as `BTC-USDC` to `BTC-USD`.
+    /// Products without an alias resolve to themselves. `None` if the product or a product in the
+    /// chain is not in the catalog, or if the aliases form a cycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID of the product to resolve.
+    pub fn resolve_alias(&self, product_id: &str) -> Option<&Product> {
+        let mut visited = HashSet::new();
+        let mut product = self.get(product_id)?;
+        while !product.alias.is_empty() && product.alias != product.product_id {
+            if !visited.insert(product.product_id.as_str()) {
+                return None;
+            }
+            product = self.get(&product.alias)?;
+        }
+
+        Some(product)
+    }
+
+    /// Products in the catalog, in no particular order.
+    pub fn products(&self) -> impl Iterator<Item = &Product> {
+        self.products.values()
+    }
+
+    /// Amount of products in the catalog.
+    pub fn len(&self) -> usize {
+        self.products.len()
+    }
+
+    /// Checks if the catalog has no products.
+    pub fn is_empty(&self) -> bool {
+        self.products.is_empty()
+    }
+}
+
 /// Represents a Bid or an Ask entry for a product.
+///
+/// Prices and sizes are accepted as numbers or strings, including scientific notation. Empty
+/// strings are treated as zero.
+///
+/// ```
+/// use cbadv::models::product::BidAsk;
+///
+/// let entry: BidAsk = serde_json::from_str(r#"{"price": "1.5E-8", "size": ""}"#).unwrap();
+/// assert_eq!(entry.price, 1.5e-8);
+/// assert_eq!(entry.size, 0.0);
+///
+/// let entry: BidAsk = serde_json::from_str(r#"{"price": 5, "size": 2.5}"#).unwrap();
+/// assert_eq!(entry.price, 5.0);
+/// assert_eq!(entry.size, 2.5);
+///
+/// let entry: BidAsk = serde_json::from_str(r#"{"price": " 5 ", "size": "2.50"}"#).unwrap();
+/// assert_eq!(entry.price, 5.0);
+/// assert_eq!(entry.size, 2.5);
+/// ```
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BidAsk {
     /// Current bid or ask price.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub price: f64,
     /// Current bid or ask size.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub size: f64,
 }
 
@@ -279,6 +644,219 @@ pub struct ProductBook {
     pub spread_absolute: String,
 }
 
+impl ProductBook {
+    /// Estimates the fill of a market order by walking the levels of the book. Buys consume the
+    /// asks and sells consume the bids.
+    ///
+    /// Returns the average and worst price as `(average, worst)`, or `None` if the book does not
+    /// have enough liquidity to fill the size.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_side` - Side of the market order.
+    /// * `size` - Size of the order, in base currency.
+    pub fn market_impact(&self, order_side: &OrderSide, size: f64) -> Option<(f64, f64)> {
+        let levels = match order_side {
+            OrderSide::Buy => &self.asks,
+            OrderSide::Sell => &self.bids,
+            OrderSide::Unknown => return None,
+        };
+
+        if size <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = size;
+        let mut notional = 0.0;
+        for level in levels {
+            let filled = remaining.min(level.size);
+            notional += filled * level.price;
+            remaining -= filled;
+
+            if remaining <= 0.0 {
+                return Some((notional / size, level.price));
+            }
+        }
+
+        None
+    }
+
+    /// Whether the book is crossed or locked, the best bid is greater than or equal to the best
+    /// ask. This can occur when a snapshot is assembled from updates received out of order, prices
+    /// from a crossed book should not be acted on. `false` if either side of the book is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::product::ProductBook;
+    ///
+    /// let book: ProductBook = serde_json::from_str(
+    ///     r#"{"product_id":"BTC-USD","time":"",
+    ///         "bids":[{"price":"101","size":"1"}],"asks":[{"price":"100","size":"1"}]}"#,
+    /// )
+    /// .unwrap();
+    /// assert!(book.is_crossed());
+    /// ```
+    pub fn is_crossed(&self) -> bool {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => bid.price >= ask.price,
+            _ => false,
+        }
+    }
+
+    /// Mid price and spread in basis points as `(mid, spread_bps)`. Uses the values provided by
+    /// the API when populated, otherwise they are computed from the best bid and ask. `None` if
+    /// either side of the book is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::product::ProductBook;
+    ///
+    /// let book: ProductBook = serde_json::from_str(
+    ///     r#"{"product_id":"BTC-USD","time":"",
+    ///         "bids":[{"price":"99","size":"1"}],"asks":[{"price":"101","size":"1"}]}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(book.mid_and_spread(), Some((100.0, 200.0)));
+    /// ```
+    pub fn mid_and_spread(&self) -> Option<(f64, f64)> {
+        let provided = |value: &str| value.parse::<f64>().ok().filter(|v| v.is_finite());
+        if let (Some(mid), Some(spread)) = (
+            provided(&self.mid_market).filter(|mid| *mid > 0.0),
+            provided(&self.spread_bps),
+        ) {
+            return Some((mid, spread));
+        }
+
+        let bid = self.bids.first()?.price;
+        let ask = self.asks.first()?.price;
+        let mid = f64::midpoint(bid, ask);
+        if mid <= 0.0 {
+            return None;
+        }
+
+        Some((mid, (ask - bid) / mid * 10_000.0))
+    }
+}
+
+/// Top of book for many products, maintained from repeated `ProductApi::best_bid_ask` requests.
+/// Keeps the latest book for each product along with the time it was last updated, allowing
+/// products that stopped updating to be found.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cbadv::models::product::{BestBidAskBook, ProductBook};
+///
+/// let latest: Vec<ProductBook> = serde_json::from_str(r#"[
+///     {"product_id": "BTC-USD", "time": "2024-01-01T00:00:00Z", "bids": [{"price": "99", "size": "1"}], "asks": [{"price": "101", "size": "1"}]},
+///     {"product_id": "ETH-USD", "time": "", "bids": [{"price": "99", "size": "1"}], "asks": [{"price": "101", "size": "1"}]}
+/// ]"#).unwrap();
+/// let older: Vec<ProductBook> = serde_json::from_str(r#"[
+///     {"product_id": "BTC-USD", "time": "2023-01-01T00:00:00Z", "bids": [{"price": "99", "size": "1"}], "asks": [{"price": "101", "size": "1"}]}
+/// ]"#).unwrap();
+///
+/// let mut books = BestBidAskBook::new();
+/// books.ingest(latest);
+/// // Older quotes do not replace newer ones.
+/// books.ingest(older);
+///
+/// assert_eq!(books.len(), 2);
+/// assert_eq!(books.updated_at("BTC-USD"), Some(1_704_067_200));
+/// assert_eq!(books.stale(Duration::from_secs(3600)), vec!["BTC-USD".to_string()]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BestBidAskBook {
+    /// Latest book and the time it was updated. [key: Product Id, value: (Timestamp, Book)]
+    books: HashMap<String, (u64, ProductBook)>,
+}
+
+impl BestBidAskBook {
+    /// Creates a new, empty, book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingests the books from a `best_bid_ask` response, replacing the books of products that are
+    /// older. Books are timestamped with their `time`, or the current time if it is not present.
+    /// Returns the amount of products that were updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `books` - Books obtained from the API.
+    pub fn ingest(&mut self, books: Vec<ProductBook>) -> usize {
+        let now = time::now();
+        let mut updated = 0;
+        for book in books {
+            let timestamp = time::from_rfc3339(&book.time).unwrap_or(now);
+            let is_newer = self
+                .books
+                .get(&book.product_id)
+                .is_none_or(|(current, _)| timestamp >= *current);
+
+            if is_newer {
+                self.books
+                    .insert(book.product_id.clone(), (timestamp, book));
+                updated += 1;
+            }
+        }
+
+        updated
+    }
+
+    /// Obtains the latest book for a product.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID to obtain the book for.
+    pub fn get(&self, product_id: &str) -> Option<&ProductBook> {
+        self.books.get(product_id).map(|(_, book)| book)
+    }
+
+    /// Time the book for a product was last updated, in UNIX time.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID to obtain the time for.
+    pub fn updated_at(&self, product_id: &str) -> Option<u64> {
+        self.books.get(product_id).map(|(timestamp, _)| *timestamp)
+    }
+
+    /// Product IDs whose book is older than the threshold, sorted in ascending order.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Age after which a book is considered stale.
+    pub fn stale(&self, threshold: Duration) -> Vec<String> {
+        let now = time::now();
+        let mut stale: Vec<String> = self
+            .books
+            .iter()
+            .filter(|(_, (timestamp, _))| now.saturating_sub(*timestamp) > threshold.as_secs())
+            .map(|(product_id, _)| product_id.clone())
+            .collect();
+
+        stale.sort();
+        stale
+    }
+
+    /// Latest books for all products, in no particular order.
+    pub fn books(&self) -> impl Iterator<Item = &ProductBook> {
+        self.books.values().map(|(_, book)| book)
+    }
+
+    /// Amount of products in the book.
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Checks if the book has no products.
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
+
 /// Represents a candle for a product.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -303,12 +881,169 @@ pub struct Candle {
     pub volume: f64,
 }
 
+#[cfg(feature = "websocket")]
 impl From<CandleUpdate> for Candle {
     fn from(candle_update: CandleUpdate) -> Self {
         candle_update.data
     }
 }
 
+/// Merges historical candles (such as from the REST API) with live candles (such as from the
+/// WebSocket). Candles are unioned by their `start` time, preferring the live candle for any
+/// bucket present in both. The resulting candles are sorted by `start` in ascending order.
+///
+/// # Arguments
+///
+/// * `historical` - Candles obtained from a backfill.
+/// * `live` - Candles obtained from a live feed, these take priority on overlap.
+pub fn merge_candles(historical: Vec<Candle>, live: Vec<Candle>) -> Vec<Candle> {
+    let mut merged: BTreeMap<u64, Candle> = BTreeMap::new();
+    for candle in historical.into_iter().chain(live) {
+        merged.insert(candle.start, candle);
+    }
+
+    merged.into_values().collect()
+}
+
+/// Groups candles into trading sessions, splitting the series at day boundaries in the timezone
+/// provided. Each session is keyed by its start time in UNIX time, the local midnight of the day,
+/// with the sessions and their candles sorted by `start` in ascending order.
+///
+/// # Arguments
+///
+/// * `candles` - Candles to group.
+/// * `tz_offset_secs` - Offset of the timezone from UTC, in seconds. Such as `-18000` for UTC-5.
+///
+/// ```
+/// use cbadv::models::product::{by_session, Candle};
+///
+/// // 2024-01-01 04:00 UTC and 06:00 UTC fall on different days in UTC-5.
+/// let candles: Vec<Candle> = serde_json::from_str(r#"[
+///     {"start": "1704081600", "low": "1", "high": "1", "open": "1", "close": "1", "volume": "1"},
+///     {"start": "1704088800", "low": "1", "high": "1", "open": "1", "close": "1", "volume": "1"}
+/// ]"#).unwrap();
+///
+/// let sessions = by_session(&candles, -18_000);
+/// assert_eq!(sessions.len(), 2);
+/// assert_eq!(sessions[0].0, 1_703_998_800);
+/// assert_eq!(sessions[1].0, 1_704_085_200);
+///
+/// // Both are on the same day in UTC.
+/// let sessions = by_session(&candles, 0);
+/// assert_eq!(sessions.len(), 1);
+/// assert_eq!(sessions[0].1.len(), 2);
+/// ```
+pub fn by_session(candles: &[Candle], tz_offset_secs: i64) -> Vec<(u64, Vec<Candle>)> {
+    const DAY_SECS: i64 = 86_400;
+
+    let mut sessions: BTreeMap<u64, Vec<Candle>> = BTreeMap::new();
+    for candle in candles {
+        let local = i64::try_from(candle.start)
+            .unwrap_or(i64::MAX)
+            .saturating_add(tz_offset_secs);
+        let session = local.div_euclid(DAY_SECS) * DAY_SECS - tz_offset_secs;
+        sessions
+            .entry(u64::try_from(session).unwrap_or_default())
+            .or_default()
+            .push(candle.clone());
+    }
+
+    sessions
+        .into_iter()
+        .map(|(session, mut candles)| {
+            candles.sort_by_key(|candle| candle.start);
+            (session, candles)
+        })
+        .collect()
+}
+
+/// Sorts a copy of the candles by their `start` time in ascending order.
+fn sorted_by_start(candles: &[Candle]) -> Vec<&Candle> {
+    let mut sorted: Vec<&Candle> = candles.iter().collect();
+    sorted.sort_by_key(|candle| candle.start);
+    sorted
+}
+
+/// Average True Range (ATR) of the candles using Wilder's smoothing. The true range of a candle
+/// is the largest of its high-low range and the distance of its high and low from the previous
+/// close, accounting for gaps between candles. The ATR is seeded with the average of the first
+/// `period` true ranges and smoothed over the remaining candles. The candles are sorted by `start`
+/// before use, so they may be in any order. Returns `None` if `period` is zero or there are fewer
+/// than `period + 1` candles.
+///
+/// # Arguments
+///
+/// * `candles` - Candles to compute the ATR for.
+/// * `period` - Amount of true ranges to average over.
+///
+/// ```
+/// use cbadv::models::product::{atr, Candle};
+///
+/// // The second candle gaps up, the true range extends from the previous close.
+/// let candles: Vec<Candle> = serde_json::from_str(r#"[
+///     {"start": "0", "low": "9", "high": "11", "open": "10", "close": "10", "volume": "1"},
+///     {"start": "60", "low": "13", "high": "14", "open": "14", "close": "14", "volume": "1"},
+///     {"start": "120", "low": "13", "high": "15", "open": "14", "close": "14", "volume": "1"}
+/// ]"#).unwrap();
+///
+/// assert_eq!(atr(&candles, 2), Some(3.0));
+/// assert_eq!(atr(&candles, 3), None);
+/// ```
+pub fn atr(candles: &[Candle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() < period + 1 {
+        return None;
+    }
+
+    let candles = sorted_by_start(candles);
+    let true_ranges: Vec<f64> = candles
+        .windows(2)
+        .map(|pair| {
+            let (previous, current) = (pair[0], pair[1]);
+            (current.high - current.low)
+                .max((current.high - previous.close).abs())
+                .max((current.low - previous.close).abs())
+        })
+        .collect();
+
+    let (seed, rest) = true_ranges.split_at(period);
+    let period = f64::from(u32::try_from(period).unwrap_or(u32::MAX));
+    let initial = seed.iter().sum::<f64>() / period;
+    Some(
+        rest.iter()
+            .fold(initial, |atr, tr| (atr * (period - 1.0) + tr) / period),
+    )
+}
+
+/// Logarithmic returns between the closes of consecutive candles, `ln(close / previous close)`.
+/// The candles are sorted by `start` before use, so they may be in any order. The result has one
+/// fewer value than there are candles, a close that is not positive produces a non-finite return.
+///
+/// # Arguments
+///
+/// * `candles` - Candles to compute the returns for.
+///
+/// ```
+/// use cbadv::models::product::{log_returns, Candle};
+///
+/// // Candles from the API are newest first.
+/// let candles: Vec<Candle> = serde_json::from_str(r#"[
+///     {"start": "120", "low": "100", "high": "100", "open": "100", "close": "100", "volume": "1"},
+///     {"start": "60", "low": "200", "high": "200", "open": "200", "close": "200", "volume": "1"},
+///     {"start": "0", "low": "100", "high": "100", "open": "100", "close": "100", "volume": "1"}
+/// ]"#).unwrap();
+///
+/// let returns = log_returns(&candles);
+/// assert_eq!(returns.len(), 2);
+/// assert!((returns[0] - 2f64.ln()).abs() < 1e-12);
+/// assert!((returns[1] + 2f64.ln()).abs() < 1e-12);
+/// ```
+pub fn log_returns(candles: &[Candle]) -> Vec<f64> {
+    sorted_by_start(candles)
+        .windows(2)
+        .map(|pair| (pair[1].close / pair[0].close).ln())
+        .collect()
+}
+
 /// Represents a trade for a product.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -318,10 +1053,12 @@ pub struct Trade {
     /// The trading pair.
     pub product_id: String,
     /// The price of the trade, in quote currency.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub price: f64,
     /// The size of the trade, in base currency.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub size: f64,
     /// The time of the trade.
     pub time: String,
@@ -338,15 +1075,17 @@ pub struct Ticker {
     /// List of trades for the product.
     pub trades: Vec<Trade>,
     /// The best bid for the `product_id`, in quote currency.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub best_bid: f64,
     /// The best ask for the `product_id`, in quote currency.
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
+    #[serde(default)]
     pub best_ask: f64,
 }
 
 /// Represents parameters that are optional for List Products API request.
-#[derive(Serialize, Default, Debug)]
+#[derive(Serialize, Default, Debug, Clone)]
 pub struct ProductListQuery {
     /// A limit describing how many products to return.
     pub limit: Option<u32>,
@@ -360,10 +1099,20 @@ pub struct ProductListQuery {
     pub get_all_products: Option<bool>,
     /// Whether or not to populate `view_only` with the tradability status of the product. This is only enabled for SPOT products.
     pub get_tradability_status: Option<bool>,
+    /// Venue of the products to return. Valid options: CBE, FCM, or INTX
+    pub product_venue: Option<ProductVenue>,
+    /// Whether the product IDs are checked to be in the `BASE-QUOTE` format before the request
+    /// is sent. Disabled by default.
+    #[serde(skip)]
+    pub validate_product_ids: bool,
 }
 
 impl Query for ProductListQuery {
     fn check(&self) -> CbResult<()> {
+        if self.validate_product_ids {
+            check_product_ids(self.product_ids.iter().flatten())?;
+        }
+
         if let Some(limit) = self.limit {
             if limit == 0 {
                 return Err(CbError::BadQuery(
@@ -383,6 +1132,12 @@ impl Query for ProductListQuery {
                 ));
             }
         }
+
+        if let Some(ProductVenue::Unknown) = &self.product_venue {
+            return Err(CbError::BadQuery(
+                "product_venue cannot be unknown".to_string(),
+            ));
+        }
         Ok(())
     }
 
@@ -394,6 +1149,7 @@ impl Query for ProductListQuery {
             .push_optional_vec("product_ids", &self.product_ids)
             .push_optional("get_all_products", &self.get_all_products)
             .push_optional("get_tradability_status", &self.get_tradability_status)
+            .push_optional("product_venue", &self.product_venue)
             .build()
     }
 }
@@ -439,6 +1195,19 @@ impl ProductListQuery {
         self.get_tradability_status = Some(get_tradability_status);
         self
     }
+
+    /// Venue of the products to return. Valid options: CBE, FCM, or INTX.
+    pub fn product_venue(mut self, product_venue: ProductVenue) -> Self {
+        self.product_venue = Some(product_venue);
+        self
+    }
+
+    /// Checks the product IDs are in the `BASE-QUOTE` format before the request is sent, catching
+    /// typos such as `BTCUSD` without a request to the API.
+    pub fn validate_product_ids(mut self, validate: bool) -> Self {
+        self.validate_product_ids = validate;
+        self
+    }
 }
 
 /// Represents parameters for Ticker Product API request.
@@ -523,10 +1292,17 @@ impl ProductTickerQuery {
 pub struct ProductBidAskQuery {
     /// The list of trading pairs (e.g. 'BTC-USD').
     pub product_ids: Vec<String>,
+    /// Whether the product IDs are checked to be in the `BASE-QUOTE` format before the request
+    /// is sent. Disabled by default.
+    #[serde(skip)]
+    pub validate_product_ids: bool,
 }
 
 impl Query for ProductBidAskQuery {
     fn check(&self) -> CbResult<()> {
+        if self.validate_product_ids {
+            check_product_ids(&self.product_ids)?;
+        }
         Ok(())
     }
 
@@ -548,6 +1324,13 @@ impl ProductBidAskQuery {
         self.product_ids = product_ids.to_vec();
         self
     }
+
+    /// Checks the product IDs are in the `BASE-QUOTE` format before the request is sent, catching
+    /// typos such as `BTCUSD` without a request to the API.
+    pub fn validate_product_ids(mut self, validate: bool) -> Self {
+        self.validate_product_ids = validate;
+        self
+    }
 }
 
 /// Represents parameters for Ticker Product API request.
@@ -559,13 +1342,22 @@ pub struct ProductBookQuery {
     pub limit: Option<u32>,
     /// The minimum price intervals at which buy and sell orders are grouped or combined in the order book.
     pub aggregation_price_increment: Option<f64>,
+    /// Whether the product ID is checked to be in the `BASE-QUOTE` format before the request
+    /// is sent. Disabled by default.
+    #[serde(skip)]
+    pub validate_product_id: bool,
 }
 
 impl Query for ProductBookQuery {
     fn check(&self) -> CbResult<()> {
         if self.product_id.is_empty() {
             return Err(CbError::BadQuery("product_id is required".to_string()));
-        } else if let Some(limit) = self.limit {
+        }
+
+        if self.validate_product_id {
+            check_product_ids([&self.product_id])?;
+        }
+        if let Some(limit) = self.limit {
             if limit == 0 {
                 return Err(CbError::BadQuery(
                     "limit must be greater than 0".to_string(),
@@ -623,6 +1415,13 @@ impl ProductBookQuery {
         self.aggregation_price_increment = Some(aggregation_price_increment);
         self
     }
+
+    /// Checks the product ID is in the `BASE-QUOTE` format before the request is sent, catching
+    /// typos such as `BTCUSD` without a request to the API.
+    pub fn validate_product_id(mut self, validate: bool) -> Self {
+        self.validate_product_id = validate;
+        self
+    }
 }
 
 /// Represents parameters for Candles Product API request.
@@ -698,6 +1497,42 @@ impl ProductCandleQuery {
         }
     }
 
+    /// Creates a new `ProductCandleQuery` object from RFC3339 timestamps.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start time of the time range, such as `2024-01-01T00:00:00Z`.
+    /// * `end` - The end time of the time range, such as `2024-01-02T00:00:00Z`.
+    /// * `granularity` - The granularity of the candles.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::BadParse` - If either timestamp is malformed or before the UNIX epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::product::ProductCandleQuery;
+    /// use cbadv::time::Granularity;
+    ///
+    /// let query = ProductCandleQuery::from_rfc3339(
+    ///     "2024-01-01T00:00:00Z",
+    ///     "2024-01-01T01:00:00+00:00",
+    ///     Granularity::OneMinute,
+    /// )
+    /// .unwrap();
+    /// assert_eq!((query.start, query.end), (1_704_067_200, 1_704_070_800));
+    ///
+    /// assert!(ProductCandleQuery::from_rfc3339("yesterday", "today", Granularity::OneMinute).is_err());
+    /// ```
+    pub fn from_rfc3339(start: &str, end: &str, granularity: Granularity) -> CbResult<Self> {
+        Ok(Self::new(
+            time::from_rfc3339(start)?,
+            time::from_rfc3339(end)?,
+            granularity,
+        ))
+    }
+
     /// The start time of the time range.
     /// Note: This is a required field.
     pub fn start(mut self, start: u64) -> Self {
@@ -724,6 +1559,60 @@ impl ProductCandleQuery {
         self.limit = limit;
         self
     }
+    /// Splits the span of the query into consecutive queries that each obtain at most the maximum
+    /// amount of candles allowed by the API in a single request.
+    pub(crate) fn batches(&self) -> Vec<Self> {
+        let interval_seconds = u64::from(Granularity::to_secs(&self.granularity));
+        let batch_seconds = interval_seconds * u64::from(CANDLE_MAXIMUM);
+
+        let mut batches = Vec::new();
+        let mut current_start = self.start;
+        while current_start < self.end {
+            let current_end = std::cmp::min(time::after(current_start, batch_seconds), self.end);
+            batches.push(Self {
+                start: current_start,
+                end: current_end,
+                granularity: self.granularity.clone(),
+                limit: CANDLE_MAXIMUM,
+            });
+            current_start = current_end;
+        }
+        batches
+    }
+}
+
+impl From<&ProductCandleQuery> for time::Span {
+    /// Converts the query into the legacy `Span`, the limit of the query is not kept.
+    fn from(query: &ProductCandleQuery) -> Self {
+        time::Span::new(query.start, query.end, &query.granularity)
+    }
+}
+
+impl From<&time::Span> for ProductCandleQuery {
+    /// Converts the legacy `Span` into a query, allowing `Span`-based code to migrate gradually.
+    /// The limit is set to the maximum amount of candles per request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cbadv::models::product::ProductCandleQuery;
+    /// use cbadv::time::{Granularity, Span};
+    ///
+    /// let span = Span::new(1_704_067_200, 1_704_070_800, &Granularity::OneMinute);
+    /// let query = ProductCandleQuery::from(&span);
+    /// assert_eq!((query.start, query.end), (span.start, span.end));
+    /// assert_eq!(query.granularity, Granularity::OneMinute);
+    ///
+    /// let span = Span::from(&query);
+    /// assert_eq!(span.granularity, 60);
+    /// ```
+    fn from(span: &time::Span) -> Self {
+        ProductCandleQuery::new(
+            span.start,
+            span.end,
+            Granularity::from_secs(span.granularity),
+        )
+    }
 }
 
 /// Represents a list of Products received from the API.
@@ -2,6 +2,8 @@
 //!
 //! `shared` gives access to utilities that will be reused throughout the API and user.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
@@ -11,6 +13,7 @@ use serde_with::{serde_as, DisplayFromStr};
 pub struct Balance {
     /// Value for the currency available or held.
     #[serde_as(as = "DisplayFromStr")]
+    #[serde(alias = "amount")]
     pub value: f64,
     /// Denomination of the currency.
     pub currency: String,
@@ -22,3 +25,74 @@ impl Balance {
         Self { value, currency }
     }
 }
+
+/// Snapshot of exchange rates quoted against a single base currency. Deserializes from the `data`
+/// object returned by <https://api.coinbase.com/v2/exchange-rates>.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::shared::{Balance, ExchangeRates};
+///
+/// let json = r#"{"currency": "USD", "rates": {"EUR": "0.5", "BTC": "0.00002"}}"#;
+/// let rates: ExchangeRates = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(rates.rate("USD", "EUR"), Some(0.5));
+/// assert_eq!(rates.rate("EUR", "USD"), Some(2.0));
+///
+/// let converted = rates.convert(&Balance::new(1.0, "BTC".to_string()), "EUR").unwrap();
+/// assert!((converted.value - 25_000.0).abs() < 1e-6);
+/// assert_eq!(converted.currency, "EUR");
+/// assert!(rates.rate("USD", "JPY").is_none());
+/// ```
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExchangeRates {
+    /// Base currency the rates are quoted against.
+    pub currency: String,
+    /// Units of each currency that equal one unit of the base currency.
+    #[serde_as(as = "HashMap<_, DisplayFromStr>")]
+    pub rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    /// Creates a new set of exchange rates quoted against the base currency.
+    pub fn new(currency: String, rates: HashMap<String, f64>) -> Self {
+        Self { currency, rates }
+    }
+
+    /// Units of `to` that equal one unit of `from`. Returns `None` if either currency has no
+    /// positive rate in the snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Currency being converted from.
+    /// * `to` - Currency being converted to.
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+
+        Some(self.per_base(to)? / self.per_base(from)?)
+    }
+
+    /// Converts the balance into the target currency. Returns `None` if a rate is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `balance` - Balance to convert.
+    /// * `currency` - Currency to convert the balance to.
+    pub fn convert(&self, balance: &Balance, currency: &str) -> Option<Balance> {
+        let rate = self.rate(&balance.currency, currency)?;
+        Some(Balance::new(balance.value * rate, currency.to_string()))
+    }
+
+    /// Units of the currency that equal one unit of the base currency.
+    fn per_base(&self, currency: &str) -> Option<f64> {
+        if currency == self.currency {
+            return Some(1.0);
+        }
+
+        self.rates.get(currency).copied().filter(|rate| *rate > 0.0)
+    }
+}
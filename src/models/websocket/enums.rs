@@ -17,6 +17,7 @@ pub enum Channel {
     /// Real-time price updates every 5000 milli-seconds.
     TickerBatch,
     /// All updates and easiest way to keep order book snapshot
+    #[serde(alias = "l2_data")]
     Level2,
     /// Real-time updates every time a market trade happens.
     MarketTrades,
@@ -28,22 +29,51 @@ pub enum Channel {
     FuturesBalanceSummary,
     /// Updates to subscription status.
     Subscriptions,
+    /// Assigned locally to messages that do not belong to a channel, such as connection lifecycle
+    /// updates and errors sent by the API. Cannot be subscribed to.
+    #[serde(skip)]
+    Unknown,
 }
 
-#[derive(Serialize, SerdeDeserialize, PartialEq, Debug)]
+#[derive(Serialize, SerdeDeserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum EventType {
     Snapshot,
     Update,
 }
 
-#[derive(Serialize, SerdeDeserialize, PartialEq, Debug)]
+#[derive(Serialize, SerdeDeserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum Level2Side {
     Bid,
+    #[serde(alias = "offer")]
     Ask,
 }
 
+/// Kind of a message, separating updates on a channel from the messages that are not.
+#[derive(Serialize, SerdeDeserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    /// Updates sent by the API on a subscribed channel.
+    Update,
+    /// Connection lifecycle updates produced locally by the client, such as disconnects and
+    /// reconnects. These do not belong to a channel.
+    Connection,
+    /// Errors sent by the API, such as when a subscription is rejected. Identified by the `type`
+    /// of the message being `error`.
+    Error,
+}
+
+/// Status of the connection for a connection lifecycle event.
+#[derive(Serialize, SerdeDeserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    /// Connection was lost, a reconnect is attempted if enabled.
+    Disconnected,
+    /// Connection was reestablished and subscriptions restored.
+    Reconnected,
+}
+
 /// Types for the endpoints.
 #[derive(PartialEq, Debug, Eq, Clone, Hash)]
 pub enum EndpointType {
@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
+use serde_json::Value;
 
 use super::{
-    CandleUpdate, EventType, FuturesBalanceSummaryUpdate, Level2Update, MarketTradesUpdate,
-    OrderUpdate, ProductUpdate, SubscribeUpdate, TickerUpdate,
+    CandleUpdate, Channel, ConnectionStatus, EventType, FuturesBalanceSummaryUpdate, Level2Update,
+    MarketTradesUpdate, OrderUpdate, ProductUpdate, ReconnectWindow, TickerUpdate, UserPositions,
 };
 
 /// Events that could be received in a message.
@@ -16,8 +19,65 @@ pub enum Event {
     User(UserEvent),
     MarketTrades(MarketTradesEvent),
     Heartbeats(HeartbeatsEvent),
-    Subscribe(SubscribeEvent),
+    Subscribe(SubscriptionsEvent),
     FuturesBalanceSummary(FuturesSummaryBalanceEvent),
+    Connection(ConnectionEvent),
+    Error(ErrorEvent),
+}
+
+impl Event {
+    /// Product IDs referenced by the event, in the order they appear. Events that are not tied
+    /// to a product, such as heartbeats, return an empty vector.
+    pub fn product_ids(&self) -> Vec<&str> {
+        match self {
+            Event::Status(event) => event.products.iter().map(|p| p.id.as_str()).collect(),
+            Event::Candles(event) => event
+                .candles
+                .iter()
+                .map(|c| c.product_id.as_str())
+                .collect(),
+            Event::Ticker(event) | Event::TickerBatch(event) => event
+                .tickers
+                .iter()
+                .map(|t| t.product_id.as_str())
+                .collect(),
+            Event::Level2(event) => vec![event.product_id.as_str()],
+            Event::User(event) => {
+                let positions = &event.positions;
+                event
+                    .orders
+                    .iter()
+                    .map(|o| o.product_id.as_str())
+                    .chain(
+                        positions
+                            .perpetual_futures_positions
+                            .iter()
+                            .map(|p| p.product_id.as_str()),
+                    )
+                    .chain(
+                        positions
+                            .expiring_futures_positions
+                            .iter()
+                            .map(|p| p.product_id.as_str()),
+                    )
+                    .collect()
+            }
+            Event::MarketTrades(event) => {
+                event.trades.iter().map(|t| t.product_id.as_str()).collect()
+            }
+            Event::Subscribe(event) => event
+                .subscriptions
+                .values()
+                .chain(event.other.values())
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+            Event::Error(event) => event.product_ids.iter().map(String::as_str).collect(),
+            Event::Heartbeats(_) | Event::FuturesBalanceSummary(_) | Event::Connection(_) => {
+                vec![]
+            }
+        }
+    }
 }
 
 /// The status event containing updates to products.
@@ -49,11 +109,64 @@ pub struct Level2Event {
     pub updates: Vec<Level2Update>,
 }
 
-/// The user event containing updates to orders.
+/// The user event containing updates to orders and the positions of futures and perpetuals.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::websocket::{Event, Message};
+///
+/// let frame = r#"{
+///     "channel": "user",
+///     "client_id": "",
+///     "timestamp": "2024-01-01T00:00:00Z",
+///     "sequence_num": 1,
+///     "events": [{
+///         "type": "update",
+///         "orders": [],
+///         "positions": {
+///             "perpetual_futures_positions": [{
+///                 "product_id": "BTC-PERP-INTX",
+///                 "portfolio_uuid": "abc",
+///                 "vwap": "50000",
+///                 "entry_vwap": "49000",
+///                 "position_side": "Long",
+///                 "margin_type": "Cross",
+///                 "net_size": "0.5",
+///                 "buy_order_size": "0",
+///                 "sell_order_size": "0",
+///                 "leverage": "2",
+///                 "mark_price": "51000",
+///                 "liquidation_price": "30000",
+///                 "im_notional": "12750",
+///                 "mm_notional": "765",
+///                 "position_notional": "25500",
+///                 "unrealized_pnl": "500",
+///                 "aggregated_pnl": "1000"
+///             }],
+///             "expiring_futures_positions": []
+///         }
+///     }]
+/// }"#;
+///
+/// let message: Message = serde_json::from_str(frame).unwrap();
+/// let Event::User(event) = &message.events[0] else {
+///     panic!("expected a user event");
+/// };
+/// let position = &event.positions.perpetual_futures_positions[0];
+/// assert_eq!(position.product_id, "BTC-PERP-INTX");
+/// assert_eq!(position.unrealized_pnl, 500.0);
+/// ```
 #[derive(Deserialize, Debug)]
 pub struct UserEvent {
+    /// Whether the event is a snapshot or an update.
     pub r#type: EventType,
+    /// Updates to the orders of the user.
+    #[serde(default)]
     pub orders: Vec<OrderUpdate>,
+    /// Positions of the user, empty if not provided.
+    #[serde(default)]
+    pub positions: UserPositions,
 }
 
 /// The market trades event containing updates to trades.
@@ -66,14 +179,91 @@ pub struct MarketTradesEvent {
 /// The heartbeats event containing the current time and heartbeat counter.
 #[derive(Deserialize, Debug)]
 pub struct HeartbeatsEvent {
+    /// Time the heartbeat was sent.
     pub current_time: String,
+    /// Counter incremented by one for every heartbeat sent on the connection.
     pub heartbeat_counter: u64,
 }
 
-/// The subscribe event containing the current subscriptions.
+/// The subscriptions event containing the current active subscriptions.
 #[derive(Deserialize, Debug)]
-pub struct SubscribeEvent {
-    pub subscriptions: SubscribeUpdate,
+#[serde(from = "RawSubscriptionsEvent")]
+pub struct SubscriptionsEvent {
+    /// Active subscriptions, channels mapped to the product IDs subscribed to.
+    pub subscriptions: HashMap<Channel, Vec<String>>,
+    /// Active subscriptions to channels that are not known, keyed by the name of the channel.
+    pub other: HashMap<String, Vec<String>>,
+}
+
+/// Previous name of `SubscriptionsEvent`.
+#[deprecated(note = "Use `SubscriptionsEvent` instead.")]
+pub type SubscribeEvent = SubscriptionsEvent;
+
+impl SubscriptionsEvent {
+    /// Product IDs actively subscribed to for a channel, empty if not subscribed.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - Channel to obtain the product IDs for.
+    pub fn products(&self, channel: &Channel) -> &[String] {
+        self.subscriptions.get(channel).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Subscriptions event as sent by the API, before the channels are parsed.
+#[derive(Deserialize)]
+struct RawSubscriptionsEvent {
+    /// Channel names mapped to the product IDs subscribed to.
+    subscriptions: HashMap<String, Option<Vec<String>>>,
+}
+
+impl From<RawSubscriptionsEvent> for SubscriptionsEvent {
+    /// Splits the subscriptions into known channels, keeping unknown channels by name.
+    fn from(raw: RawSubscriptionsEvent) -> Self {
+        let mut event = SubscriptionsEvent {
+            subscriptions: HashMap::new(),
+            other: HashMap::new(),
+        };
+
+        for (name, products) in raw.subscriptions {
+            let products = products.unwrap_or_default();
+            match serde_json::from_value::<Channel>(Value::String(name.clone())) {
+                Ok(channel) => {
+                    event.subscriptions.insert(channel, products);
+                }
+                Err(_) => {
+                    event.other.insert(name, products);
+                }
+            }
+        }
+        event
+    }
+}
+
+/// Connection lifecycle event produced locally by the client, delivered in a message with the
+/// `MessageKind::Connection` kind.
+#[derive(Debug, Clone)]
+pub struct ConnectionEvent {
+    /// Current status of the connection.
+    pub status: ConnectionStatus,
+    /// Reason for the disconnect, empty for reconnects.
+    pub reason: String,
+    /// Window of time the connection was lost for, only present for reconnects.
+    pub window: Option<ReconnectWindow>,
+}
+
+/// Error sent by the API, delivered in a message with the `MessageKind::Error` kind. Sent when a request such as a
+/// subscription is refused.
+#[derive(Debug, Clone)]
+pub struct ErrorEvent {
+    /// Description of the error.
+    pub message: String,
+    /// Additional details on why the error occurred, empty if not provided.
+    pub reason: String,
+    /// Channel of the refused subscription, if provided.
+    pub channel: Option<Channel>,
+    /// Product IDs of the refused subscription, empty if not provided.
+    pub product_ids: Vec<String>,
 }
 
 /// The futures summary balance event containing the current futures account balance.
@@ -1,17 +1,58 @@
 use std::fmt;
 
+use chrono::{DateTime, SecondsFormat};
 use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
 use serde_json::Value;
 
 use super::{
-    CandlesEvent, Channel, Event, FuturesSummaryBalanceEvent, HeartbeatsEvent, Level2Event,
-    MarketTradesEvent, StatusEvent, SubscribeEvent, TickerEvent, UserEvent,
+    CandlesEvent, Channel, ConnectionEvent, ConnectionStatus, ErrorEvent, Event,
+    FuturesSummaryBalanceEvent, HeartbeatsEvent, Level2Event, MarketTradesEvent, MessageKind,
+    ReconnectWindow, StatusEvent, SubscriptionsEvent, TickerEvent, UserEvent,
 };
 
-/// Message from the WebSocket containing event updates.
+/// Message from the WebSocket containing event updates. A single frame may batch several events,
+/// every event in the frame is kept in `events` in the order it was received. Errors sent by the
+/// API have the `MessageKind::Error` kind with a single `Event::Error` event. Connection lifecycle
+/// messages are produced locally, they have the `MessageKind::Connection` kind. Neither belong to
+/// a channel, they are on the `Channel::Unknown` channel.
+///
+/// # Examples
+///
+/// ```
+/// use cbadv::models::websocket::{Channel, Event, Message, MessageKind};
+///
+/// let frame = r#"{
+///     "channel": "l2_data",
+///     "client_id": "",
+///     "timestamp": "2024-01-01T00:00:00Z",
+///     "sequence_num": 7,
+///     "events": [
+///         {"type": "update", "product_id": "BTC-USD", "updates": [
+///             {"side": "bid", "event_time": "2024-01-01T00:00:00Z", "price_level": "100.0", "new_quantity": "1.5"}
+///         ]},
+///         {"type": "update", "product_id": "BTC-USD", "updates": [
+///             {"side": "offer", "event_time": "2024-01-01T00:00:00Z", "price_level": "101.0", "new_quantity": "0"}
+///         ]}
+///     ]
+/// }"#;
+///
+/// let message: Message = serde_json::from_str(frame).unwrap();
+/// assert_eq!(message.kind(), MessageKind::Update);
+/// assert_eq!(message.channel, Channel::Level2);
+/// assert_eq!(message.events.len(), 2);
+/// assert!(message.events.iter().all(|event| matches!(event, Event::Level2(_))));
+///
+/// let frame = r#"{"type": "error", "message": "failure to subscribe", "reason": "bad channel"}"#;
+/// let message: Message = serde_json::from_str(frame).unwrap();
+/// assert_eq!(message.kind(), MessageKind::Error);
+/// assert_eq!(message.channel, Channel::Unknown);
+/// let error = message.error().unwrap();
+/// assert_eq!(error.message, "failure to subscribe");
+/// assert_eq!(error.reason, "bad channel");
+/// ```
 #[derive(Debug)]
 pub struct Message {
-    /// The channel the message is from.
+    /// The channel the message is from, `Channel::Unknown` if it does not belong to a channel.
     pub channel: Channel,
     /// The client ID for the message.
     pub client_id: String,
@@ -23,6 +64,99 @@ pub struct Message {
     pub events: Vec<Event>,
 }
 
+impl Message {
+    /// Product IDs referenced by all of the events within the message. Each product ID is only
+    /// included once, in the order it first appears.
+    pub fn product_ids(&self) -> Vec<&str> {
+        let mut product_ids: Vec<&str> = Vec::new();
+        for product_id in self.events.iter().flat_map(Event::product_ids) {
+            if !product_ids.contains(&product_id) {
+                product_ids.push(product_id);
+            }
+        }
+        product_ids
+    }
+
+    /// Creates a connection lifecycle message indicating the connection was lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Reason for the disconnect.
+    /// * `disconnected_at` - UNIX timestamp of when the disconnect was detected.
+    pub(crate) fn disconnected(reason: &str, disconnected_at: u64) -> Self {
+        Self::connection(
+            disconnected_at,
+            ConnectionEvent {
+                status: ConnectionStatus::Disconnected,
+                reason: reason.to_string(),
+                window: None,
+            },
+        )
+    }
+
+    /// Creates a connection lifecycle message indicating the connection was reestablished.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Window of time the connection was lost for.
+    pub(crate) fn reconnected(window: ReconnectWindow) -> Self {
+        Self::connection(
+            window.reconnected_at,
+            ConnectionEvent {
+                status: ConnectionStatus::Reconnected,
+                reason: String::new(),
+                window: Some(window),
+            },
+        )
+    }
+
+    /// Creates a connection lifecycle message containing the event.
+    fn connection(timestamp: u64, event: ConnectionEvent) -> Self {
+        let timestamp = i64::try_from(timestamp)
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default();
+
+        Self {
+            channel: Channel::Unknown,
+            client_id: String::new(),
+            timestamp,
+            sequence_num: 0,
+            events: vec![Event::Connection(event)],
+        }
+    }
+
+    /// Kind of the message, determined by its events. Messages containing an `Event::Connection`
+    /// are connection lifecycle messages and those containing an `Event::Error` are errors.
+    pub fn kind(&self) -> MessageKind {
+        self.events
+            .iter()
+            .find_map(|event| match event {
+                Event::Connection(_) => Some(MessageKind::Connection),
+                Event::Error(_) => Some(MessageKind::Error),
+                _ => None,
+            })
+            .unwrap_or(MessageKind::Update)
+    }
+
+    /// Error sent by the API if the message is an error message.
+    pub fn error(&self) -> Option<&ErrorEvent> {
+        self.events.iter().find_map(|event| match event {
+            Event::Error(event) => Some(event),
+            _ => None,
+        })
+    }
+
+    /// Connection status if the message is a connection lifecycle message.
+    pub fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.events.iter().find_map(|event| match event {
+            Event::Connection(event) => Some(event.status),
+            _ => None,
+        })
+    }
+}
+
 /// Custom deserialization for Message.
 impl<'de> Deserialize<'de> for Message {
     fn deserialize<D>(deserializer: D) -> Result<Message, D::Error>
@@ -52,6 +186,10 @@ impl<'de> Visitor<'de> for MessageVisitor {
         let mut timestamp: Option<String> = None;
         let mut sequence_num: Option<u64> = None;
         let mut events_value: Option<Value> = None;
+        let mut message_type: Option<String> = None;
+        let mut error_message: Option<String> = None;
+        let mut error_reason: Option<String> = None;
+        let mut product_ids: Option<Vec<String>> = None;
 
         // Extract common fields and store the raw events for later deserialization.
         while let Some(key) = map.next_key::<&str>()? {
@@ -87,6 +225,11 @@ impl<'de> Visitor<'de> for MessageVisitor {
                     // Temporarily store events as serde_json::Value
                     events_value = Some(map.next_value()?);
                 }
+                // Fields only present on error messages.
+                "type" => message_type = Some(map.next_value()?),
+                "message" => error_message = Some(map.next_value()?),
+                "reason" => error_reason = Some(map.next_value()?),
+                "product_ids" => product_ids = Some(map.next_value()?),
                 _ => {
                     // Skip unknown fields or handle as needed.
                     let _ = map.next_value::<de::IgnoredAny>()?;
@@ -94,6 +237,22 @@ impl<'de> Visitor<'de> for MessageVisitor {
             }
         }
 
+        // Errors are not sent on a channel and do not contain events.
+        if message_type.as_deref() == Some("error") {
+            return Ok(Message {
+                channel: Channel::Unknown,
+                client_id: client_id.unwrap_or_default(),
+                timestamp: timestamp.unwrap_or_default(),
+                sequence_num: sequence_num.unwrap_or_default(),
+                events: vec![Event::Error(ErrorEvent {
+                    message: error_message.unwrap_or_default(),
+                    reason: error_reason.unwrap_or_default(),
+                    channel,
+                    product_ids: product_ids.unwrap_or_default(),
+                })],
+            });
+        }
+
         let channel = channel.ok_or_else(|| de::Error::missing_field("channel"))?;
         let client_id = client_id.ok_or_else(|| de::Error::missing_field("client_id"))?;
         let timestamp = timestamp.ok_or_else(|| de::Error::missing_field("timestamp"))?;
@@ -152,9 +311,10 @@ fn deserialize_events(
             Ok(events.into_iter().map(Event::Heartbeats).collect())
         }
         Channel::Subscriptions => {
-            let events: Vec<SubscribeEvent> = serde_json::from_value(events_value)?;
+            let events: Vec<SubscriptionsEvent> = serde_json::from_value(events_value)?;
             Ok(events.into_iter().map(Event::Subscribe).collect())
         }
+        Channel::Unknown => Err("unknown channel is not sent by the API".into()),
         Channel::FuturesBalanceSummary => {
             let events: Vec<FuturesSummaryBalanceEvent> = serde_json::from_value(events_value)?;
             Ok(events
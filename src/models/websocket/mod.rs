@@ -8,10 +8,12 @@ mod enums;
 mod events;
 mod message;
 mod responses;
+mod trackers;
 mod types;
 
 pub use enums::*;
 pub use events::*;
 pub use message::*;
 pub use responses::*;
+pub use trackers::*;
 pub use types::*;
@@ -3,6 +3,7 @@ use serde_with::{serde_as, DefaultOnError, DisplayFromStr};
 
 use crate::models::order::{OrderSide, OrderStatus, OrderType, TimeInForce, TriggerStatus};
 use crate::models::product::{Candle, ProductType};
+use crate::utils::NumericOrEmpty;
 
 use super::Level2Side;
 
@@ -17,22 +18,32 @@ pub struct Level2Update {
     pub new_quantity: f64,
 }
 
-#[derive(Deserialize, Debug, Default)]
-pub struct SubscribeUpdate {
-    #[serde(default)]
-    pub status: Vec<String>,
-    #[serde(default)]
-    pub ticker: Vec<String>,
-    #[serde(default)]
-    pub ticker_batch: Vec<String>,
-    #[serde(default)]
-    pub level2: Option<Vec<String>>,
-    #[serde(default)]
-    pub user: Option<Vec<String>>,
-    #[serde(default)]
-    pub market_trades: Option<Vec<String>>,
-    #[serde(default)]
-    pub heartbeats: Option<Vec<String>>,
+#[allow(deprecated)]
+pub use legacy::SubscribeUpdate;
+
+#[allow(deprecated)]
+mod legacy {
+    use serde::Deserialize;
+
+    /// Previous representation of the active subscriptions, replaced by `SubscriptionsEvent`.
+    #[deprecated(note = "Use `SubscriptionsEvent` instead.")]
+    #[derive(Deserialize, Debug, Default)]
+    pub struct SubscribeUpdate {
+        #[serde(default)]
+        pub status: Vec<String>,
+        #[serde(default)]
+        pub ticker: Vec<String>,
+        #[serde(default)]
+        pub ticker_batch: Vec<String>,
+        #[serde(default)]
+        pub level2: Option<Vec<String>>,
+        #[serde(default)]
+        pub user: Option<Vec<String>>,
+        #[serde(default)]
+        pub market_trades: Option<Vec<String>>,
+        #[serde(default)]
+        pub heartbeats: Option<Vec<String>>,
+    }
 }
 
 /// Represents a Product received from the Websocket API.
@@ -62,6 +73,18 @@ pub struct ProductUpdate {
     /// Minimum amount of funds.
     #[serde_as(as = "DisplayFromStr")]
     pub min_market_funds: f64,
+    /// Whether trading is currently disabled (halted) for the product.
+    #[serde(default)]
+    pub trading_disabled: bool,
+    /// Whether the product is in limit-only mode.
+    #[serde(default)]
+    pub limit_only: bool,
+    /// Whether the product is in cancel-only mode.
+    #[serde(default)]
+    pub cancel_only: bool,
+    /// Whether the product is in post-only mode.
+    #[serde(default)]
+    pub post_only: bool,
 }
 
 /// Represents a Market Trade received from the Websocket API.
@@ -129,28 +152,28 @@ pub struct TickerUpdate {
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderUpdate {
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub avg_price: f64,
     pub cancel_reason: String,
     pub client_order_id: String,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub completion_percentage: f64,
     pub contract_expiry_type: String,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub cumulative_quantity: f64,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub filled_value: f64,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub leaves_quantity: f64,
-    #[serde_as(as = "DefaultOnError<DisplayFromStr>")]
+    #[serde_as(as = "DefaultOnError<NumericOrEmpty>")]
     #[serde(default)]
     pub limit_price: f64,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub number_of_fills: u32,
     pub order_id: String,
     pub order_side: OrderSide,
     pub order_type: OrderType,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub outstanding_hold_amount: f64,
     #[serde_as(as = "DisplayFromStr")]
     pub post_only: bool,
@@ -164,9 +187,9 @@ pub struct OrderUpdate {
     #[serde(default)]
     pub stop_price: Option<f64>,
     pub time_in_force: TimeInForce,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub total_fees: f64,
-    #[serde_as(as = "DisplayFromStr")]
+    #[serde_as(as = "NumericOrEmpty")]
     pub total_value_after_fees: f64,
     pub trigger_status: TriggerStatus,
     pub creation_time: String,
@@ -174,6 +197,94 @@ pub struct OrderUpdate {
     pub start_time: String,
 }
 
+/// Positions for a user from a websocket, sent alongside order updates on the user channel.
+/// Position arrays that are omitted, such as for spot-only accounts, are empty.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UserPositions {
+    /// Open perpetual futures positions.
+    pub perpetual_futures_positions: Vec<PerpetualFuturesPositionUpdate>,
+    /// Open expiring futures positions.
+    pub expiring_futures_positions: Vec<ExpiringFuturesPositionUpdate>,
+}
+
+/// Perpetual futures position update for a user from a websocket.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PerpetualFuturesPositionUpdate {
+    /// Product of the position, such as BTC-PERP-INTX.
+    pub product_id: String,
+    /// Portfolio that holds the position.
+    pub portfolio_uuid: String,
+    /// Volume weighted average price of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub vwap: f64,
+    /// Volume weighted average price the position was entered at.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub entry_vwap: f64,
+    /// Side of the position, such as Long or Short.
+    pub position_side: String,
+    /// Margin type of the position, such as Cross or Isolated.
+    pub margin_type: String,
+    /// Size of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub net_size: f64,
+    /// Size of the open buy orders.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub buy_order_size: f64,
+    /// Size of the open sell orders.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub sell_order_size: f64,
+    /// Leverage of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub leverage: f64,
+    /// Current mark price of the product.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub mark_price: f64,
+    /// Price at which the position is liquidated.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub liquidation_price: f64,
+    /// Initial margin notional of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub im_notional: f64,
+    /// Maintenance margin notional of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub mm_notional: f64,
+    /// Notional value of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub position_notional: f64,
+    /// Unrealized profit and loss of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub unrealized_pnl: f64,
+    /// Aggregated profit and loss of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub aggregated_pnl: f64,
+}
+
+/// Expiring futures position update for a user from a websocket.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ExpiringFuturesPositionUpdate {
+    /// Product of the position, such as BIT-31MAY24-CDE.
+    pub product_id: String,
+    /// Side of the position, such as Long or Short.
+    pub side: String,
+    /// Amount of contracts held.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub number_of_contracts: f64,
+    /// Realized profit and loss of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub realized_pnl: f64,
+    /// Unrealized profit and loss of the position.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub unrealized_pnl: f64,
+    /// Average price the position was entered at.
+    #[serde_as(as = "NumericOrEmpty")]
+    pub entry_price: f64,
+}
+
 /// Represents a Futures Balance Summary update received from the Websocket API.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
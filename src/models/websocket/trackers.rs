@@ -0,0 +1,351 @@
+//! # Coinbase Advanced Websocket Trackers
+//!
+//! `websocket/trackers` contains helpers that maintain running state across a stream of updates.
+
+use std::collections::HashMap;
+
+use super::{Event, HeartbeatsEvent, Message, ProductUpdate, TickerUpdate};
+
+/// Running statistics for a single product since tracking began.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickerSession {
+    /// Highest price observed since tracking began.
+    pub session_high: f64,
+    /// Lowest price observed since tracking began.
+    pub session_low: f64,
+    /// Most recent price observed.
+    pub last: f64,
+    /// Approximate volume traded since tracking began, accumulated from increases in the
+    /// rolling 24hr volume.
+    pub volume_since_start: f64,
+    /// Most recent rolling 24hr volume observed, used to calculate volume changes.
+    last_volume_24_h: f64,
+}
+
+impl TickerSession {
+    /// Creates a new session from the first ticker update for a product.
+    fn new(ticker: &TickerUpdate) -> Self {
+        Self {
+            session_high: ticker.price,
+            session_low: ticker.price,
+            last: ticker.price,
+            volume_since_start: 0.0,
+            last_volume_24_h: ticker.volume_24_h,
+        }
+    }
+
+    /// Updates the session with a new ticker update.
+    fn update(&mut self, ticker: &TickerUpdate) {
+        self.session_high = self.session_high.max(ticker.price);
+        self.session_low = self.session_low.min(ticker.price);
+        self.last = ticker.price;
+
+        // Only increases are counted since the 24hr volume is a rolling window.
+        let volume_change = ticker.volume_24_h - self.last_volume_24_h;
+        if volume_change > 0.0 {
+            self.volume_since_start += volume_change;
+        }
+        self.last_volume_24_h = ticker.volume_24_h;
+    }
+}
+
+/// Tracks session (since tracking began) extremes, last price, and volume for products from the
+/// Ticker and Ticker Batch channels.
+#[derive(Debug, Clone, Default)]
+pub struct TickerTracker {
+    /// Sessions for each product. [key: Product Id, value: Session]
+    sessions: HashMap<String, TickerSession>,
+}
+
+impl TickerTracker {
+    /// Creates a new, empty `TickerTracker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the tracker with a single ticker update.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticker` - Ticker update to process.
+    pub fn update(&mut self, ticker: &TickerUpdate) {
+        if let Some(session) = self.sessions.get_mut(&ticker.product_id) {
+            session.update(ticker);
+        } else {
+            self.sessions
+                .insert(ticker.product_id.clone(), TickerSession::new(ticker));
+        }
+    }
+
+    /// Updates the tracker with all ticker updates contained in a message. Messages from other
+    /// channels are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message received from the WebSocket.
+    pub fn ingest(&mut self, message: &Message) {
+        for event in &message.events {
+            if let Event::Ticker(ticker_event) | Event::TickerBatch(ticker_event) = event {
+                for ticker in &ticker_event.tickers {
+                    self.update(ticker);
+                }
+            }
+        }
+    }
+
+    /// Obtains the session for a product, if any updates have been received for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to obtain the session for.
+    pub fn get(&self, product_id: &str) -> Option<&TickerSession> {
+        self.sessions.get(product_id)
+    }
+
+    /// Highest price observed for a product since tracking began.
+    pub fn session_high(&self, product_id: &str) -> Option<f64> {
+        self.get(product_id).map(|session| session.session_high)
+    }
+
+    /// Lowest price observed for a product since tracking began.
+    pub fn session_low(&self, product_id: &str) -> Option<f64> {
+        self.get(product_id).map(|session| session.session_low)
+    }
+
+    /// Most recent price observed for a product.
+    pub fn last(&self, product_id: &str) -> Option<f64> {
+        self.get(product_id).map(|session| session.last)
+    }
+
+    /// Approximate volume traded for a product since tracking began.
+    pub fn volume_since_start(&self, product_id: &str) -> Option<f64> {
+        self.get(product_id)
+            .map(|session| session.volume_since_start)
+    }
+
+    /// Removes all tracked sessions, starting tracking over.
+    pub fn reset(&mut self) {
+        self.sessions.clear();
+    }
+}
+
+/// Trading state of a product, obtained from the Status channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketState {
+    /// Status of the product, such as "online".
+    pub status: String,
+    /// Whether trading is disabled (halted).
+    pub trading_disabled: bool,
+    /// Whether only limit orders are accepted.
+    pub limit_only: bool,
+    /// Whether only cancellations are accepted.
+    pub cancel_only: bool,
+    /// Whether only post-only orders are accepted.
+    pub post_only: bool,
+}
+
+impl MarketState {
+    /// Whether new orders can be placed without restrictions.
+    pub fn is_unrestricted(&self) -> bool {
+        self.status.eq_ignore_ascii_case("online")
+            && !self.trading_disabled
+            && !self.limit_only
+            && !self.cancel_only
+            && !self.post_only
+    }
+}
+
+impl From<&ProductUpdate> for MarketState {
+    fn from(product: &ProductUpdate) -> Self {
+        Self {
+            status: product.status.clone(),
+            trading_disabled: product.trading_disabled,
+            limit_only: product.limit_only,
+            cancel_only: product.cancel_only,
+            post_only: product.post_only,
+        }
+    }
+}
+
+/// Change in the trading state of a product.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketStateChange {
+    /// Product that changed.
+    pub product_id: String,
+    /// Previous state of the product.
+    pub from: MarketState,
+    /// New state of the product.
+    pub to: MarketState,
+}
+
+/// Tracks the trading state of products from the Status channel, reporting when a product is
+/// halted, resumed, or enters a restricted mode such as limit-only or cancel-only.
+#[derive(Debug, Clone, Default)]
+pub struct MarketStateTracker {
+    /// Latest state for each product. [key: Product Id, value: State]
+    states: HashMap<String, MarketState>,
+}
+
+impl MarketStateTracker {
+    /// Creates a new, empty `MarketStateTracker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the tracker with a single product update. Returns the change if the state of a
+    /// previously seen product differs, the first update for a product only records its state.
+    ///
+    /// # Arguments
+    ///
+    /// * `product` - Product update to process.
+    pub fn update(&mut self, product: &ProductUpdate) -> Option<MarketStateChange> {
+        let state = MarketState::from(product);
+        match self.states.insert(product.id.clone(), state.clone()) {
+            Some(previous) if previous != state => Some(MarketStateChange {
+                product_id: product.id.clone(),
+                from: previous,
+                to: state,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Updates the tracker with all product updates contained in a message, returning every
+    /// change detected. Messages from other channels are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message received from the WebSocket.
+    pub fn ingest(&mut self, message: &Message) -> Vec<MarketStateChange> {
+        let mut changes = vec![];
+        for event in &message.events {
+            if let Event::Status(status_event) = event {
+                for product in &status_event.products {
+                    changes.extend(self.update(product));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Obtains the latest state for a product, if any updates have been received for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product to obtain the state for.
+    pub fn get(&self, product_id: &str) -> Option<&MarketState> {
+        self.states.get(product_id)
+    }
+
+    /// Removes all tracked states, starting tracking over.
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+}
+
+/// Heartbeats that were not received, detected by a skip in the heartbeat counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatGap {
+    /// Counter of the heartbeat that was expected next.
+    pub expected: u64,
+    /// Counter of the heartbeat that was received.
+    pub received: u64,
+}
+
+impl HeartbeatGap {
+    /// Amount of heartbeats that were missed.
+    pub fn missed(&self) -> u64 {
+        self.received - self.expected
+    }
+}
+
+/// Tracks the counter of the Heartbeats channel, reporting when heartbeats are skipped. This is a
+/// deterministic liveness signal that complements timing-based checks. The counter is restarted
+/// by the API on a new connection, so it is forgotten on reconnects and when it moves backwards.
+///
+/// ```
+/// use cbadv::models::websocket::{HeartbeatTracker, HeartbeatsEvent};
+///
+/// let heartbeats: Vec<HeartbeatsEvent> = serde_json::from_str(r#"[
+///     {"current_time": "2024-01-01 00:00:00 +0000 UTC", "heartbeat_counter": 10},
+///     {"current_time": "2024-01-01 00:00:01 +0000 UTC", "heartbeat_counter": 11},
+///     {"current_time": "2024-01-01 00:00:04 +0000 UTC", "heartbeat_counter": 14}
+/// ]"#).unwrap();
+///
+/// let mut tracker = HeartbeatTracker::new();
+/// assert_eq!(tracker.update(&heartbeats[0]), None);
+/// assert_eq!(tracker.update(&heartbeats[1]), None);
+///
+/// let gap = tracker.update(&heartbeats[2]).unwrap();
+/// assert_eq!(gap.missed(), 2);
+/// assert_eq!(tracker.missed(), 2);
+/// assert_eq!(tracker.last_counter(), Some(14));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeartbeatTracker {
+    /// Counter of the last heartbeat received on the current connection.
+    last_counter: Option<u64>,
+    /// Total amount of heartbeats missed since tracking began.
+    missed: u64,
+}
+
+impl HeartbeatTracker {
+    /// Creates a new, empty `HeartbeatTracker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the tracker with a single heartbeat. Returns the gap if one or more heartbeats
+    /// were skipped since the last heartbeat.
+    ///
+    /// # Arguments
+    ///
+    /// * `heartbeat` - Heartbeat event to process.
+    pub fn update(&mut self, heartbeat: &HeartbeatsEvent) -> Option<HeartbeatGap> {
+        let received = heartbeat.heartbeat_counter;
+        let expected = self.last_counter.replace(received)?.checked_add(1)?;
+        if received > expected {
+            let gap = HeartbeatGap { expected, received };
+            self.missed += gap.missed();
+            Some(gap)
+        } else {
+            None
+        }
+    }
+
+    /// Updates the tracker with all heartbeats contained in a message, returning every gap
+    /// detected. Reconnects and disconnects in connection lifecycle messages forget the last
+    /// counter, messages from other channels are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message received from the WebSocket.
+    pub fn ingest(&mut self, message: &Message) -> Vec<HeartbeatGap> {
+        let mut gaps = vec![];
+        for event in &message.events {
+            match event {
+                Event::Heartbeats(heartbeat) => gaps.extend(self.update(heartbeat)),
+                Event::Connection(_) => self.last_counter = None,
+                _ => {}
+            }
+        }
+        gaps
+    }
+
+    /// Counter of the last heartbeat received, `None` if none have been received since tracking
+    /// began or the last reconnect.
+    pub fn last_counter(&self) -> Option<u64> {
+        self.last_counter
+    }
+
+    /// Total amount of heartbeats missed since tracking began.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Forgets the last counter and the amount missed, starting tracking over.
+    pub fn reset(&mut self) {
+        self.last_counter = None;
+        self.missed = 0;
+    }
+}
@@ -161,7 +161,8 @@ impl WebSocketSubscriptions {
             .or_insert_with(|| product_ids.to_vec());
     }
 
-    /// Remove the specified product IDs from the subscriptions.
+    /// Remove the specified product IDs from the subscriptions. The channel is no longer tracked
+    /// if no product IDs are provided or none remain.
     pub(crate) async fn remove(
         &mut self,
         channel: &Channel,
@@ -174,6 +175,9 @@ impl WebSocketSubscriptions {
             // Remove the product IDs from the subscriptions.
             if let Some(ids) = subs.get_mut(channel) {
                 ids.retain(|id| !product_ids.contains(id));
+                if product_ids.is_empty() || ids.is_empty() {
+                    subs.remove(channel);
+                }
             }
         }
     }
@@ -188,6 +192,17 @@ impl WebSocketSubscriptions {
         }
     }
 
+    /// Checks if the product ID is subscribed to on any channel of any endpoint.
+    pub(crate) async fn contains_product(&self, product_id: &str) -> bool {
+        for subs_mutex in self.data.values() {
+            let subs = subs_mutex.lock().await;
+            if subs.values().flatten().any(|id| id == product_id) {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Obtains all of the keys (endpoints) that have subscriptions.
     pub(crate) fn get_keys(&self) -> Vec<EndpointType> {
         let keys: Vec<EndpointType> = self.data.keys().cloned().collect();
@@ -195,12 +210,54 @@ impl WebSocketSubscriptions {
     }
 }
 
+/// Window of time the WebSocket was disconnected, provided after a successful reconnect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectWindow {
+    /// UNIX timestamp of when the disconnection was detected.
+    pub disconnected_at: u64,
+    /// UNIX timestamp of when the connection was reestablished.
+    pub reconnected_at: u64,
+}
+
+impl ReconnectWindow {
+    /// Amount of seconds the WebSocket was disconnected for.
+    pub fn downtime(&self) -> u64 {
+        self.reconnected_at.saturating_sub(self.disconnected_at)
+    }
+}
+
+type SourceStream = Pin<Box<dyn Stream<Item = Result<WsMessage, WsError>> + Send>>;
+
 /// Stream of WebSocket messages from one or more endpoints.
 pub enum EndpointStream {
     /// A single endpoint stream.
     Single(EndpointType, SplitStream),
     /// Multiple endpoint streams.
     Multiple(SelectAll<SplitStream>),
+    /// A user-provided source of messages, such as canned frames for testing. Cannot be
+    /// reconnected and ends once the source is exhausted.
+    Source(SourceStream),
+}
+
+impl EndpointStream {
+    /// Creates an `EndpointStream` from an arbitrary source of messages. This allows messages to
+    /// be fed through the same processing used for live endpoints, such as for testing.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Stream of messages to process.
+    pub fn from_source<S>(source: S) -> Self
+    where
+        S: Stream<Item = Result<WsMessage, WsError>> + Send + 'static,
+    {
+        EndpointStream::Source(Box::pin(source))
+    }
+}
+
+impl From<Vec<WsMessage>> for EndpointStream {
+    fn from(messages: Vec<WsMessage>) -> Self {
+        EndpointStream::from_source(stream::iter(messages.into_iter().map(Ok)))
+    }
 }
 
 impl From<Endpoint> for EndpointStream {
@@ -242,6 +299,7 @@ impl Stream for EndpointStream {
         match self.get_mut() {
             EndpointStream::Single(_, stream) => Pin::new(stream).poll_next(cx),
             EndpointStream::Multiple(stream) => Pin::new(stream).poll_next(cx),
+            EndpointStream::Source(stream) => stream.as_mut().poll_next(cx),
         }
     }
 }
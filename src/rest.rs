@@ -5,35 +5,79 @@
 //! negotiations for the user.
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use futures::lock::Mutex;
+#[cfg(feature = "trade")]
+use reqwest::Method;
+#[cfg(feature = "trade")]
+use serde::de::DeserializeOwned;
+use tokio::sync::{Mutex, Semaphore};
 
+use crate::apis::PublicApi;
+#[cfg(feature = "trade")]
 use crate::apis::{
     AccountApi, ConvertApi, DataApi, FeeApi, OrderApi, PaymentApi, PortfolioApi, ProductApi,
-    PublicApi,
 };
-use crate::http_agent::{PublicHttpAgent, SecureHttpAgent};
+#[cfg(feature = "trade")]
+use crate::constants::accounts::CACHE_TTL_MS;
+use crate::http_agent::PublicHttpAgent;
+#[cfg(feature = "trade")]
+use crate::http_agent::SecureHttpAgent;
 
 #[cfg(feature = "config")]
 use crate::config::ConfigFile;
-use crate::token_bucket::{RateLimits, TokenBucket};
+#[cfg(feature = "trade")]
+use crate::errors::CbError;
+use crate::time::{Clock, SystemClock};
+use crate::token_bucket::{RateLimits, ThrottleCallback, TokenBucket};
+#[cfg(feature = "trade")]
+use crate::traits::{HttpAgent, RawQuery};
 use crate::types::CbResult;
 
 /// Builds a new REST Client (`RestClient`) that directly interacts with the Coinbase Advanced API.
-#[derive(Default)]
 pub struct RestClientBuilder {
+    #[cfg(feature = "trade")]
     api_key: Option<String>,
+    #[cfg(feature = "trade")]
     api_secret: Option<String>,
+    #[cfg(feature = "trade")]
+    api_passphrase: Option<String>,
     use_sandbox: bool,
+    strict_json: bool,
+    clock: Arc<dyn Clock>,
+    #[cfg(feature = "trade")]
+    account_cache_ttl: Duration,
+    #[cfg(feature = "trade")]
+    cache_jwt: bool,
+    on_throttle: Option<Arc<ThrottleCallback>>,
+    max_concurrency: Option<usize>,
+}
+
+impl Default for RestClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RestClientBuilder {
     /// Creates a new instance of a `RestClientBuilder`.
     pub fn new() -> Self {
         Self {
+            #[cfg(feature = "trade")]
             api_key: None,
+            #[cfg(feature = "trade")]
             api_secret: None,
+            #[cfg(feature = "trade")]
+            api_passphrase: None,
             use_sandbox: false,
+            strict_json: false,
+            clock: Arc::new(SystemClock),
+            #[cfg(feature = "trade")]
+            account_cache_ttl: Duration::from_millis(CACHE_TTL_MS),
+            #[cfg(feature = "trade")]
+            cache_jwt: false,
+            on_throttle: None,
+            max_concurrency: None,
         }
     }
 
@@ -47,8 +91,11 @@ impl RestClientBuilder {
     where
         T: ConfigFile,
     {
-        self.api_key = Some(config.coinbase().api_key.clone());
-        self.api_secret = Some(config.coinbase().api_secret.clone());
+        #[cfg(feature = "trade")]
+        {
+            self.api_key = Some(config.coinbase().api_key.clone());
+            self.api_secret = Some(config.coinbase().api_secret.clone());
+        }
         self.use_sandbox = config.coinbase().use_sandbox;
         self
     }
@@ -59,12 +106,34 @@ impl RestClientBuilder {
     ///
     /// * `key` - API key.
     /// * `secret` - API secret.
+    #[cfg(feature = "trade")]
     pub fn with_authentication(mut self, key: &str, secret: &str) -> Self {
         self.api_key = Some(key.to_string());
         self.api_secret = Some(secret.to_string());
+        self.api_passphrase = None;
         self
     }
 
+    /// Uses the provided key and PEM-encoded secret to initialize the authentication. The secret
+    /// is decrypted with the passphrase if one is provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - API key.
+    /// * `secret_pem` - API secret, a private key in PEM format.
+    /// * `passphrase` - Passphrase the private key is encrypted with, if any.
+    #[cfg(feature = "trade")]
+    pub fn with_authentication_pem(
+        self,
+        key: &str,
+        secret_pem: &str,
+        passphrase: Option<&str>,
+    ) -> Self {
+        let mut builder = self.with_authentication(key, secret_pem);
+        builder.api_passphrase = passphrase.map(str::to_string);
+        builder
+    }
+
     /// Sets the `use_sandbox` flag for the client.
     ///
     /// # Arguments
@@ -75,6 +144,80 @@ impl RestClientBuilder {
         self
     }
 
+    /// Sets whether responses are parsed strictly. In strict mode, any field in a response that
+    /// is not modelled by this library results in a `CbError::JsonError` naming the fields,
+    /// which is useful for detecting changes to the API. Disabled by default, unknown fields are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict_json` - A boolean that determines if unknown fields are errors.
+    pub fn strict_json(mut self, strict_json: bool) -> Self {
+        self.strict_json = strict_json;
+        self
+    }
+
+    /// Sets the clock used as the source of the current time, defaults to the system clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Clock that implements the `Clock` trait.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the amount of time accounts are served from the cache by `AccountApi::get_cached`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - Amount of time an account remains cached.
+    #[cfg(feature = "trade")]
+    pub fn account_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.account_cache_ttl = ttl;
+        self
+    }
+
+    /// Sets whether the JSON Web Tokens used to authenticate are cached. When enabled, a token is
+    /// reused for repeated requests to the same method and path until it is near expiry instead
+    /// of being signed for every request. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Enable or disable caching of tokens.
+    #[cfg(feature = "trade")]
+    pub fn cache_jwt(mut self, enable: bool) -> Self {
+        self.cache_jwt = enable;
+        self
+    }
+
+    /// Sets a hook that is called whenever a request waits on the rate limits, with the amount of
+    /// time it waited for. Useful for measuring how often and for how long requests are throttled.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Function called with the amount of time a request waited.
+    pub fn on_throttle<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.on_throttle = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the maximum amount of requests that can be in flight at once, independent of the
+    /// rate limits. The limit is shared by all APIs and clones of the client, bounding the
+    /// simultaneous connections when fanning out many requests. A limit of 0 is treated as 1.
+    /// Unlimited by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum amount of concurrent requests.
+    pub fn max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = Some(limit.max(1));
+        self
+    }
+
     /// Builds the `RestClient`.
     ///
     /// # Errors
@@ -82,64 +225,188 @@ impl RestClientBuilder {
     /// * `CbError::RequestError` - If there was an issue creating the HTTP client.
     pub fn build(self) -> CbResult<RestClient> {
         // Initialize token buckets
+        #[cfg(feature = "trade")]
         let secure_bucket = Arc::new(Mutex::new(TokenBucket::new(
             RateLimits::max_tokens(true, false),
             RateLimits::refresh_rate(true, false),
+            self.on_throttle.clone(),
         )));
 
         let public_bucket = Arc::new(Mutex::new(TokenBucket::new(
             RateLimits::max_tokens(true, true),
             RateLimits::refresh_rate(true, true),
+            self.on_throttle,
         )));
 
+        // Shared limit on the requests in flight across all agents.
+        let limiter = self
+            .max_concurrency
+            .map(|limit| Arc::new(Semaphore::new(limit)));
+
         // Initialize agents.
+        #[cfg(feature = "trade")]
         let secure_agent = if let (Some(key), Some(secret)) = (self.api_key, self.api_secret) {
             Some(SecureHttpAgent::new(
                 &key,
                 &secret,
+                self.api_passphrase.as_deref(),
                 self.use_sandbox,
+                self.strict_json,
                 secure_bucket,
+                Arc::clone(&self.clock),
             )?)
+            .map(|agent| {
+                if self.cache_jwt {
+                    agent.with_jwt_cache()
+                } else {
+                    agent
+                }
+            })
+            .map(|agent| match &limiter {
+                Some(limiter) => agent.with_limiter(Arc::clone(limiter)),
+                None => agent,
+            })
         } else {
             None
         };
 
         // Public agent used to access public endpoints.
-        let public_agent = PublicHttpAgent::new(self.use_sandbox, public_bucket)?;
+        let mut public_agent =
+            PublicHttpAgent::new(
+                self.use_sandbox,
+                self.strict_json,
+                public_bucket,
+                Arc::clone(&self.clock),
+            )?;
+        if let Some(limiter) = limiter {
+            public_agent = public_agent.with_limiter(limiter);
+        }
 
         // Initialize APIs.
         Ok(RestClient {
-            account: AccountApi::new(secure_agent.clone()),
-            product: ProductApi::new(secure_agent.clone()),
+            #[cfg(feature = "trade")]
+            account: AccountApi::new(
+                secure_agent.clone(),
+                self.account_cache_ttl,
+                Arc::clone(&self.clock),
+            ),
+            #[cfg(feature = "trade")]
+            product: ProductApi::new(secure_agent.clone(), Arc::clone(&self.clock)),
+            #[cfg(feature = "trade")]
             fee: FeeApi::new(secure_agent.clone()),
-            order: OrderApi::new(secure_agent.clone()),
+            #[cfg(feature = "trade")]
+            order: OrderApi::new(secure_agent.clone(), Arc::clone(&self.clock)),
+            #[cfg(feature = "trade")]
             portfolio: PortfolioApi::new(secure_agent.clone()),
+            #[cfg(feature = "trade")]
             convert: ConvertApi::new(secure_agent.clone()),
+            #[cfg(feature = "trade")]
             payment: PaymentApi::new(secure_agent.clone()),
+            #[cfg(feature = "trade")]
             data: DataApi::new(secure_agent.clone()),
             public: PublicApi::new(public_agent),
+            #[cfg(feature = "trade")]
+            agent: secure_agent,
         })
     }
 }
 
 /// Represents a REST Client for interacting with the Coinbase Advanced API.
+///
+/// The client is `Send + Sync` and cheap to `Clone`. Clones share the same HTTP connection pool
+/// and rate limiting token buckets, allowing a clone to be moved into each task instead of
+/// wrapping the client in a `Mutex`. Requests made from separate clones are not serialized
+/// beyond waiting on the shared rate limits.
+#[derive(Clone)]
 pub struct RestClient {
     /// Gives access to the Account API.
+    #[cfg(feature = "trade")]
     pub account: AccountApi,
     /// Gives access to the Product API.
+    #[cfg(feature = "trade")]
     pub product: ProductApi,
     /// Gives access to the Fee API.
+    #[cfg(feature = "trade")]
     pub fee: FeeApi,
     /// Gives access to the Order API.
+    #[cfg(feature = "trade")]
     pub order: OrderApi,
     /// Gives access to the Portfolio API.
+    #[cfg(feature = "trade")]
     pub portfolio: PortfolioApi,
     /// Gives access to the Convert API.
+    #[cfg(feature = "trade")]
     pub convert: ConvertApi,
     /// Gives access to the Payment API.
+    #[cfg(feature = "trade")]
     pub payment: PaymentApi,
     /// Gives access to the Data API.
+    #[cfg(feature = "trade")]
     pub data: DataApi,
     /// Gives access to the Public API.
     pub public: PublicApi,
+    /// Object used to sign arbitrary requests made to the API.
+    #[cfg(feature = "trade")]
+    agent: Option<SecureHttpAgent>,
+}
+
+#[cfg(feature = "trade")]
+impl RestClient {
+    /// Signs and sends a request to an endpoint that is not directly supported by the crate,
+    /// deserializing the response into the type provided. Authentication, rate limiting, and the
+    /// base URL are handled the same as the supported endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the request, GET, POST, etc.
+    /// * `path` - The resource being accessed (ex. "/api/v3/brokerage/accounts").
+    /// * `query` - Key-value pairs that are added to the URL query.
+    /// * `body` - JSON body of the request, if any.
+    ///
+    /// # Errors
+    ///
+    /// * `CbError::AuthenticationError` - If the agent is not authenticated.
+    /// * `CbError::JsonError` - If there was an issue parsing the JSON response.
+    /// * `CbError::RequestError` - If there was an issue making the request.
+    /// * `CbError::UrlParseError` - If there was an issue parsing the URL.
+    /// * `CbError::BadSerialization` - If there was an issue serializing the request.
+    /// * `CbError::BadStatus` - If the status code was not 200.
+    /// * `CbError::BadJwt` - If there was an issue creating the JWT.
+    pub async fn request<T: DeserializeOwned>(
+        &mut self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&serde_json::Value>,
+    ) -> CbResult<T> {
+        let agent = get_auth!(self.agent, "signed request");
+        let body = body
+            .map(|body| {
+                serde_json::to_string(body).map_err(|e| CbError::BadSerialization(e.to_string()))
+            })
+            .transpose()?;
+
+        let response = agent
+            .request(method, path, &RawQuery::new(query), body)
+            .await?;
+        agent.parse_json(response).await
+    }
+}
+
+/// Compile-time assertion that the client and APIs can be shared across threads.
+fn _assert_send_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<RestClient>();
+    #[cfg(feature = "trade")]
+    {
+        assert_send_sync::<AccountApi>();
+        assert_send_sync::<ConvertApi>();
+        assert_send_sync::<DataApi>();
+        assert_send_sync::<FeeApi>();
+        assert_send_sync::<OrderApi>();
+        assert_send_sync::<PaymentApi>();
+        assert_send_sync::<PortfolioApi>();
+        assert_send_sync::<ProductApi>();
+    }
+    assert_send_sync::<PublicApi>();
 }
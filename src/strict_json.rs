@@ -0,0 +1,249 @@
+//! # Strict JSON deserialization.
+//!
+//! `strict_json` deserializes JSON while recording every field that the target type ignores.
+//! Used to detect when the API returns fields that are not modelled by this library.
+
+use std::cell::RefCell;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::{Error, Map, Value};
+
+use crate::errors::CbError;
+use crate::types::CbResult;
+
+/// Deserializes a JSON value into `T`, erroring if the value contains fields that `T` ignores.
+///
+/// # Arguments
+///
+/// * `value` - JSON value to deserialize.
+pub(crate) fn from_value<T: DeserializeOwned>(value: &Value) -> CbResult<T> {
+    let ignored = RefCell::new(Vec::new());
+    let parsed = T::deserialize(Tracked {
+        value,
+        path: String::new(),
+        ignored: &ignored,
+    })
+    .map_err(|e| CbError::JsonError(e.to_string()))?;
+
+    let ignored = ignored.into_inner();
+    if ignored.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(CbError::JsonError(format!(
+            "unknown fields in response: {}",
+            ignored.join(", ")
+        )))
+    }
+}
+
+/// Deserializer over a JSON value that records the paths of ignored values.
+struct Tracked<'a> {
+    /// Value being deserialized.
+    value: &'a Value,
+    /// Path to the value, used when reporting ignored fields.
+    path: String,
+    /// Paths of the values ignored so far.
+    ignored: &'a RefCell<Vec<String>>,
+}
+
+impl<'a> Tracked<'a> {
+    /// Creates a deserializer for a value nested within this one.
+    fn child(&self, value: &'a Value, segment: &str) -> Self {
+        let path = if self.path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{segment}", self.path)
+        };
+
+        Self {
+            value,
+            path,
+            ignored: self.ignored,
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Tracked<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(*value),
+            Value::Number(number) => {
+                if let Some(value) = number.as_u64() {
+                    visitor.visit_u64(value)
+                } else if let Some(value) = number.as_i64() {
+                    visitor.visit_i64(value)
+                } else {
+                    visitor.visit_f64(number.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(value) => visitor.visit_str(value),
+            Value::Array(values) => visitor.visit_seq(TrackedSeq {
+                parent: &self,
+                values: values.iter().enumerate(),
+            }),
+            Value::Object(map) => visitor.visit_map(TrackedMap {
+                parent: &self,
+                entries: map.iter(),
+                next: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            Value::Object(map) if map.len() == 1 => {
+                visitor.visit_enum(TrackedEnum { parent: &self, map })
+            }
+            _ => Err(de::Error::custom(format!(
+                "expected an enum at '{}'",
+                self.path
+            ))),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.ignored.borrow_mut().push(self.path);
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// Sequence access over the elements of a JSON array.
+struct TrackedSeq<'p, 'a, I> {
+    parent: &'p Tracked<'a>,
+    values: I,
+}
+
+impl<'de, 'a, I> SeqAccess<'de> for TrackedSeq<'_, 'a, I>
+where
+    I: Iterator<Item = (usize, &'a Value)>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.values.next() {
+            Some((index, value)) => seed
+                .deserialize(self.parent.child(value, &index.to_string()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Map access over the entries of a JSON object.
+struct TrackedMap<'p, 'a, I> {
+    parent: &'p Tracked<'a>,
+    entries: I,
+    next: Option<(&'a String, &'a Value)>,
+}
+
+impl<'de, 'a, I> MapAccess<'de> for TrackedMap<'_, 'a, I>
+where
+    I: Iterator<Item = (&'a String, &'a Value)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.next = Some((key, value));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (key, value) = self
+            .next
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(self.parent.child(value, key))
+    }
+}
+
+/// Enum access over an externally tagged enum, a JSON object with a single entry.
+struct TrackedEnum<'p, 'a> {
+    parent: &'p Tracked<'a>,
+    map: &'a Map<String, Value>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for TrackedEnum<'_, 'a> {
+    type Error = Error;
+    type Variant = Tracked<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let (variant, value) = self
+            .map
+            .iter()
+            .next()
+            .ok_or_else(|| de::Error::custom("expected an enum variant"))?;
+        let parsed = seed.deserialize(variant.as_str().into_deserializer())?;
+        Ok((parsed, self.parent.child(value, variant)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Tracked<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
@@ -27,7 +27,7 @@ const SIX_HOUR: u32 = ONE_HOUR * 6;
 const ONE_DAY: u32 = ONE_HOUR * 24;
 
 /// Span of time in seconds.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Granularity {
     #[serde(rename = "UNKNOWN_GRANULARITY")]
@@ -172,6 +172,23 @@ impl Query for Span {
     }
 }
 
+/// Source of the current time. Allows a deterministic clock to be injected in place of the
+/// system clock, such as for testing time-sensitive behavior.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Obtains the current timestamp in UNIX format.
+    fn now(&self) -> u64;
+}
+
+/// Clock that reads the system time, used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        now()
+    }
+}
+
 /// Obtains the current timestamp in UNIX format.
 ///
 /// # Panics
@@ -193,3 +210,36 @@ pub fn after(timestamp: u64, seconds: u64) -> u64 {
 pub fn before(timestamp: u64, seconds: u64) -> u64 {
     timestamp - seconds
 }
+
+/// Parses an RFC3339 timestamp into a timestamp in UNIX format.
+///
+/// # Arguments
+///
+/// * `timestamp` - RFC3339 timestamp, such as `2024-01-01T00:00:00Z`.
+///
+/// # Errors
+///
+/// * `CbError::BadParse` - If the timestamp is malformed or before the UNIX epoch.
+pub fn from_rfc3339(timestamp: &str) -> CbResult<u64> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| CbError::BadParse(format!("invalid RFC3339 timestamp '{timestamp}': {e}")))?;
+    u64::try_from(parsed.timestamp())
+        .map_err(|_| CbError::BadParse(format!("timestamp '{timestamp}' is before the UNIX epoch")))
+}
+
+/// Formats a timestamp in UNIX format as an RFC3339 timestamp, such as `2024-01-01T00:00:00Z`.
+///
+/// # Arguments
+///
+/// * `timestamp` - Timestamp in UNIX format (seconds).
+///
+/// # Errors
+///
+/// * `CbError::BadParse` - If the timestamp is out of the range of representable times.
+pub fn to_rfc3339(timestamp: u64) -> CbResult<String> {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .ok_or_else(|| CbError::BadParse(format!("timestamp '{timestamp}' is out of range")))
+}
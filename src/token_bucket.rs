@@ -1,5 +1,7 @@
 //! Bucket for managing and consuming tokens to prevent API rate limiting.
 
+use std::fmt;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::sleep as async_sleep;
 
@@ -60,8 +62,11 @@ impl RateLimits {
     }
 }
 
+/// Called with the amount of time a request waited on a bucket for a token.
+pub(crate) type ThrottleCallback = dyn Fn(Duration) + Send + Sync;
+
 /// Contains and tracks token usage for rate limits.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub(crate) struct TokenBucket {
     /// Maximum amount of tokens allowed in the bucket at a time.
     max_tokens: f64,
@@ -71,6 +76,19 @@ pub(crate) struct TokenBucket {
     last_consumption: Instant,
     /// Amount of current token in the bucket.
     tokens: f64,
+    /// Called when a request had to wait for a token.
+    on_throttle: Option<Arc<ThrottleCallback>>,
+}
+
+impl fmt::Debug for TokenBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenBucket")
+            .field("max_tokens", &self.max_tokens)
+            .field("refill_rate", &self.refill_rate)
+            .field("last_consumption", &self.last_consumption)
+            .field("tokens", &self.tokens)
+            .finish_non_exhaustive()
+    }
 }
 
 impl TokenBucket {
@@ -80,12 +98,18 @@ impl TokenBucket {
     ///
     /// * `max_tokens` - Maximum amount of tokens allowed in the bucket.
     /// * `refill_rate` - How many tokens per second are refreshed.
-    pub(crate) fn new(max_tokens: f64, refill_rate: f64) -> Self {
+    /// * `on_throttle` - Called with the amount of time waited when a token was not available.
+    pub(crate) fn new(
+        max_tokens: f64,
+        refill_rate: f64,
+        on_throttle: Option<Arc<ThrottleCallback>>,
+    ) -> Self {
         Self {
             max_tokens,
             refill_rate,
             last_consumption: Instant::now(),
             tokens: max_tokens,
+            on_throttle,
         }
     }
 
@@ -119,10 +143,20 @@ impl TokenBucket {
         }
     }
 
-    /// Blocks until a token is ready and immediately consumes it.
+    /// Blocks until a token is ready and immediately consumes it. If the bucket was empty, the
+    /// throttle hook is called with the amount of time spent waiting.
     pub(crate) async fn wait_on(&mut self) {
+        if self.consume() {
+            return;
+        }
+
+        let started = Instant::now();
         while !self.consume() {
             async_sleep(self.next_token()).await;
         }
+
+        if let Some(hook) = &self.on_throttle {
+            hook(started.elapsed());
+        }
     }
 }
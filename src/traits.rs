@@ -1,13 +1,25 @@
 //! Traits used to allow interfacing with advanced functionality for end-users.
 
+#[cfg(feature = "trade")]
+use std::collections::VecDeque;
+
+#[cfg(any(feature = "trade", feature = "websocket"))]
 use async_trait::async_trait;
+#[cfg(feature = "trade")]
+use futures::stream::{self, Stream};
 use reqwest::Response;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "trade")]
 use serde::Serialize;
+#[cfg(feature = "trade")]
+use url::form_urlencoded;
 
+#[cfg(feature = "websocket")]
 use crate::models::{product::Candle, websocket::Message};
 use crate::types::CbResult;
 
 /// Used to pass to a callback to the candle watcher on a successful ejection.
+#[cfg(feature = "websocket")]
 #[async_trait]
 pub trait CandleCallback {
     /// Called when a candle is succesfully ejected.
@@ -21,6 +33,7 @@ pub trait CandleCallback {
 }
 
 /// Used to pass objects to the listener for greater control over message processing.
+#[cfg(feature = "websocket")]
 #[async_trait]
 pub trait MessageCallback {
     /// This is called when processing a message from the WebSocket.
@@ -31,6 +44,40 @@ pub trait MessageCallback {
     async fn message_callback(&mut self, msg: CbResult<Message>);
 }
 
+/// Used to lazily obtain the results of a paginated endpoint, one page at a time. Implemented by
+/// the paginators of the APIs, such as those for orders, fills, accounts, and products.
+#[cfg(feature = "trade")]
+#[async_trait]
+pub trait Paginate: Send + Sized {
+    /// Type of the items contained within a page.
+    type Item: Send;
+
+    /// Obtains the next page of items. Returns `None` once there are no additional pages. After
+    /// an error is returned, the paginator is exhausted.
+    async fn next_page(&mut self) -> Option<CbResult<Vec<Self::Item>>>;
+
+    /// Converts the paginator into a stream that yields each item, requesting pages as they are
+    /// required. The stream ends after the first error is yielded.
+    fn into_stream(self) -> impl Stream<Item = CbResult<Self::Item>> {
+        let state = (self, VecDeque::new(), false);
+
+        stream::unfold(state, |(mut pages, mut buffer, finished)| async move {
+            loop {
+                if let Some(item) = buffer.pop_front() {
+                    return Some((Ok(item), (pages, buffer, finished)));
+                } else if finished {
+                    return None;
+                }
+
+                match pages.next_page().await? {
+                    Ok(items) => buffer.extend(items),
+                    Err(err) => return Some((Err(err), (pages, buffer, true))),
+                }
+            }
+        })
+    }
+}
+
 /// Used to pass query/paramters for a URL.
 pub(crate) trait Query {
     /// Checks that the query is valid and the required fields are present.
@@ -40,6 +87,7 @@ pub(crate) trait Query {
 }
 
 /// Used to pass a request body to an endpoint.
+#[cfg(feature = "trade")]
 pub(crate) trait Request {
     /// Checks that the request is valid and the required fields are present.
     fn check(&self) -> CbResult<()>;
@@ -57,8 +105,47 @@ impl Query for NoQuery {
     }
 }
 
+/// Represents a query built from raw key-value pairs.
+#[cfg(feature = "trade")]
+pub(crate) struct RawQuery<'a> {
+    /// Key-value pairs that make up the query.
+    pairs: &'a [(&'a str, &'a str)],
+}
+
+#[cfg(feature = "trade")]
+impl<'a> RawQuery<'a> {
+    /// Creates a new `RawQuery` from key-value pairs.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - Key-value pairs that make up the query.
+    pub(crate) fn new(pairs: &'a [(&'a str, &'a str)]) -> Self {
+        Self { pairs }
+    }
+}
+
+#[cfg(feature = "trade")]
+impl Query for RawQuery<'_> {
+    fn check(&self) -> CbResult<()> {
+        Ok(())
+    }
+
+    fn to_query(&self) -> String {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.pairs)
+            .finish()
+    }
+}
+
 /// Trait for the `HttpAgent` that is responsible for making HTTP requests and managing the token bucket.
 pub(crate) trait HttpAgent {
+    /// Parses the JSON body of a response into `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response from the API.
+    async fn parse_json<T: DeserializeOwned>(&self, response: Response) -> CbResult<T>;
+
     /// Performs a HTTP GET Request.
     ///
     /// # Arguments
@@ -74,6 +161,7 @@ pub(crate) trait HttpAgent {
     /// * `resource` - A string representing the resource that is being accessed.
     /// * `query` - A string containing options / parameters for the URL.
     /// * `body` - An object to send to the URL via POST request.
+    #[cfg(feature = "trade")]
     async fn post<'a, T>(
         &mut self,
         resource: &str,
@@ -90,6 +178,7 @@ pub(crate) trait HttpAgent {
     /// * `resource` - A string representing the resource that is being accessed.
     /// * `query` - A string containing options / parameters for the URL.
     /// * `body` - An object to send to the URL via POST request.
+    #[cfg(feature = "trade")]
     async fn put<'a, T>(
         &mut self,
         resource: &str,
@@ -105,5 +194,6 @@ pub(crate) trait HttpAgent {
     ///
     /// * `resource` - A string representing the resource that is being accessed.
     /// * `query` - A string containing options / parameters for the URL.
+    #[cfg(feature = "trade")]
     async fn delete(&mut self, resource: &str, query: &impl Query) -> CbResult<Response>;
 }
@@ -1,7 +1,10 @@
 //! Contains custom / shorthand types to simplify end-user code.
 
+#[cfg(feature = "websocket")]
 use futures_util::stream::SplitStream;
+#[cfg(feature = "websocket")]
 use tokio::net::TcpStream;
+#[cfg(feature = "websocket")]
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 use crate::errors::CbError;
@@ -9,7 +12,9 @@ use crate::errors::CbError;
 /// Used to return objects from the API to the end-user.
 pub type CbResult<T> = Result<T, CbError>;
 
+#[cfg(feature = "websocket")]
 pub(crate) type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// The 'Read' of a split socket.
+#[cfg(feature = "websocket")]
 pub type WebSocketReader = SplitStream<Socket>;
@@ -3,14 +3,30 @@
 //! `utils` is a collection of helpful tools that may be required throughout the rest of the API.
 
 use std::fmt::{Display, Write};
+#[cfg(feature = "websocket")]
 use std::future::Future;
+#[cfg(feature = "websocket")]
 use std::pin::Pin;
+use std::str::FromStr;
+#[cfg(feature = "websocket")]
 use std::sync::Arc;
 
+#[cfg(feature = "websocket")]
 use async_trait::async_trait;
+#[cfg(feature = "trade")]
+use serde::Serialize;
+use serde::{de, Deserialize, Deserializer, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+#[cfg(feature = "trade")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
+#[cfg(feature = "trade")]
+use crate::errors::CbError;
+#[cfg(feature = "websocket")]
 use crate::models::websocket::Message;
+#[cfg(feature = "websocket")]
 use crate::traits::MessageCallback;
+#[cfg(any(feature = "trade", feature = "websocket"))]
 use crate::types::CbResult;
 
 /// Builds the URL Query to be sent to the API.
@@ -71,14 +87,173 @@ impl QueryBuilder {
     }
 }
 
+/// Serializes the items as newline-delimited JSON and writes them in one go.
+///
+/// # Arguments
+///
+/// * `writer` - Destination of the items.
+/// * `items` - Items to write, one per line.
+///
+/// # Errors
+///
+/// * `CbError::BadSerialization` - If there was an issue serializing an item.
+/// * `CbError::WriteError` - If there was an issue writing to the writer.
+#[cfg(feature = "trade")]
+pub(crate) async fn write_lines<W, T>(writer: &mut W, items: &[T]) -> CbResult<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut buffer = Vec::new();
+    for item in items {
+        serde_json::to_writer(&mut buffer, item)
+            .map_err(|e| CbError::BadSerialization(e.to_string()))?;
+        buffer.push(b'\n');
+    }
+
+    writer
+        .write_all(&buffer)
+        .await
+        .map_err(|e| CbError::WriteError(e.to_string()))
+}
+
+/// Numeric value that may be sent by the API as either a number or a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumericOrString {
+    Number(serde_json::Number),
+    Text(String),
+}
+
+/// Parses a numeric value. Values in scientific notation or with a fractional part of zero, such
+/// as `1E3` or `5.0`, are also accepted for integer types.
+fn parse_numeric<T>(value: &str) -> Result<T, T::Err>
+where
+    T: FromStr,
+{
+    value.parse().or_else(|err| match value.parse::<f64>() {
+        Ok(float) if float.is_finite() && float.fract() == 0.0 => format!("{float:.0}").parse(),
+        _ => Err(err),
+    })
+}
+
+/// Deserializes a numeric value that may be sent as a number, a string, an empty string, or null.
+/// Empty strings and null are treated as `None`, other unparsable values are still an error.
+/// Scientific notation, such as `1.5E-8`, is accepted.
+pub(crate) fn deserialize_optional_numeric_or_empty<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Option::<NumericOrString>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(NumericOrString::Number(value)) => parse_numeric(&value.to_string())
+            .map(Some)
+            .map_err(de::Error::custom),
+        Some(NumericOrString::Text(value)) if value.trim().is_empty() => Ok(None),
+        Some(NumericOrString::Text(value)) => parse_numeric(value.trim())
+            .map(Some)
+            .map_err(de::Error::custom),
+    }
+}
+
+/// Deserializes a numeric value that may be sent as a number, a string, an empty string, or null.
+/// Empty strings and null are treated as the default value (0 for numerics).
+pub(crate) fn deserialize_numeric_or_empty<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Default,
+    T::Err: Display,
+{
+    Ok(deserialize_optional_numeric_or_empty(deserializer)?.unwrap_or_default())
+}
+
+/// Used with `serde_as` for numeric fields that may occasionally be sent as an empty string.
+/// Deserializes using `deserialize_numeric_or_empty` and serializes as a string.
+pub(crate) struct NumericOrEmpty;
+
+impl<'de, T> DeserializeAs<'de, T> for NumericOrEmpty
+where
+    T: FromStr + Default,
+    T::Err: Display,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_numeric_or_empty(deserializer)
+    }
+}
+
+impl<T: Display> SerializeAs<T> for NumericOrEmpty {
+    fn serialize_as<S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+}
+
+/// Significant digits kept when formatting decimals sent to the API.
+const DECIMAL_SIGNIFICANT_DIGITS: usize = 15;
+
+/// Formats a float as a plain decimal string, rounded to 15 significant digits. Removes the
+/// floating-point noise from values such as `0.1 + 0.2`, which would otherwise be sent as
+/// `0.30000000000000004` and rejected by the API for having too much precision.
+pub(crate) fn format_decimal(value: f64) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let rounded = format!("{:.*e}", DECIMAL_SIGNIFICANT_DIGITS - 1, value);
+    rounded.parse::<f64>().unwrap_or(value).to_string()
+}
+
+/// Used with `serde_as` for decimals sent to the API. Serializes using `format_decimal`, and
+/// deserializes numbers or strings with empty strings treated as zero, or `None` if optional.
+pub(crate) struct DecimalString;
+
+impl SerializeAs<f64> for DecimalString {
+    fn serialize_as<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format_decimal(*value))
+    }
+}
+
+impl<'de> DeserializeAs<'de, f64> for DecimalString {
+    fn deserialize_as<D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_numeric_or_empty(deserializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Option<f64>> for DecimalString {
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_optional_numeric_or_empty(deserializer)
+    }
+}
+
+#[cfg(feature = "websocket")]
 type BoxCallback =
     Box<dyn Fn(CbResult<Message>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
 /// Used to wrap callback functions for the WebSocket Client's `listen()` function..
+#[cfg(feature = "websocket")]
 pub struct FunctionCallback {
     callback: Arc<BoxCallback>,
 }
 
+#[cfg(feature = "websocket")]
 impl FunctionCallback {
     /// Creates a new `FunctionCallback` from an asynchronous function.
     ///
@@ -127,6 +302,7 @@ impl FunctionCallback {
     }
 }
 
+#[cfg(feature = "websocket")]
 #[async_trait]
 impl MessageCallback for FunctionCallback {
     async fn message_callback(&mut self, msg: CbResult<Message>) {
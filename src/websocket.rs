@@ -4,33 +4,48 @@
 //! Many parts of the REST API suggest using websockets instead due to ratelimits and being quicker
 //! for large amount of constantly changing data.
 
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
+use futures_util::future::{self, Either};
 use futures_util::stream::{self, SplitSink};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{SinkExt, Stream, StreamExt};
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMessage};
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::{connect_async_with_config, MaybeTlsStream, WebSocketStream};
 
+#[cfg(feature = "trade")]
+use crate::apis::ProductApi;
 use crate::candle_watcher::CandleWatcher;
+#[cfg(feature = "trade")]
+use crate::constants::websocket::SUBSCRIBE_CHUNK_SIZE;
 use crate::constants::websocket::{PUBLIC_ENDPOINT, SECURE_ENDPOINT};
 use crate::errors::CbError;
 use crate::jwt::Jwt;
+#[cfg(feature = "trade")]
+use crate::models::product::ProductListQuery;
 use crate::models::websocket::{
-    Channel, Endpoint, EndpointStream, EndpointType, Message, SecureSubscription, Subscription,
-    UnsignedSubscription, WebSocketEndpoints, WebSocketSubscriptions,
+    Channel, Endpoint, EndpointStream, EndpointType, Message, ReconnectWindow, SecureSubscription,
+    Subscription, UnsignedSubscription, WebSocketEndpoints, WebSocketSubscriptions,
 };
-use crate::time;
-use crate::token_bucket::{RateLimits, TokenBucket};
+use crate::time::{Clock, SystemClock};
+use crate::token_bucket::{RateLimits, ThrottleCallback, TokenBucket};
 use crate::traits::{CandleCallback, MessageCallback};
 use crate::types::CbResult;
+use crate::websocket_pool::WebSocketPool;
 
 #[cfg(feature = "config")]
 use crate::config::ConfigFile;
 
 type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type ReconnectCallback =
+    dyn Fn(ReconnectWindow) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
 
 /// Obtains the endpoint associated with the channel.
 fn get_channel_endpoint(channel: &Channel) -> EndpointType {
@@ -42,7 +57,8 @@ fn get_channel_endpoint(channel: &Channel) -> EndpointType {
         | Channel::TickerBatch
         | Channel::MarketTrades
         | Channel::Level2
-        | Channel::Candles => EndpointType::Public,
+        | Channel::Candles
+        | Channel::Unknown => EndpointType::Public,
         Channel::User | Channel::FuturesBalanceSummary => EndpointType::User,
     }
 }
@@ -51,11 +67,17 @@ fn get_channel_endpoint(channel: &Channel) -> EndpointType {
 pub struct WebSocketClientBuilder {
     api_key: Option<String>,
     api_secret: Option<String>,
+    api_passphrase: Option<String>,
     enable_public: bool,
     enable_user: bool,
     max_retries: u32,
-    public_bucket: Arc<Mutex<TokenBucket>>,
-    secure_bucket: Arc<Mutex<TokenBucket>>,
+    clock: Arc<dyn Clock>,
+    cache_jwt: bool,
+    on_reconnect: Option<Arc<ReconnectCallback>>,
+    on_throttle: Option<Arc<ThrottleCallback>>,
+    socket_config: WebSocketConfig,
+    public_url: String,
+    secure_url: String,
 }
 
 impl Default for WebSocketClientBuilder {
@@ -63,17 +85,17 @@ impl Default for WebSocketClientBuilder {
         Self {
             api_key: None,
             api_secret: None,
+            api_passphrase: None,
             enable_public: true, // By default, enable public connection.
             enable_user: false,  // By default, do not enable secure connection.
             max_retries: 0,      // By default, do not auto-reconnect.
-            public_bucket: Arc::new(Mutex::new(TokenBucket::new(
-                RateLimits::max_tokens(false, true),
-                RateLimits::refresh_rate(false, true),
-            ))),
-            secure_bucket: Arc::new(Mutex::new(TokenBucket::new(
-                RateLimits::max_tokens(false, false),
-                RateLimits::refresh_rate(false, false),
-            ))),
+            clock: Arc::new(SystemClock),
+            cache_jwt: false,
+            on_reconnect: None,
+            on_throttle: None,
+            socket_config: WebSocketConfig::default(),
+            public_url: PUBLIC_ENDPOINT.to_string(),
+            secure_url: SECURE_ENDPOINT.to_string(),
         }
     }
 }
@@ -109,10 +131,30 @@ impl WebSocketClientBuilder {
     pub fn with_authentication(mut self, key: &str, secret: &str) -> Self {
         self.api_key = Some(key.to_string());
         self.api_secret = Some(secret.to_string());
+        self.api_passphrase = None;
         self.enable_user = true;
         self
     }
 
+    /// Uses the provided key and PEM-encoded secret to initialize the authentication. The secret
+    /// is decrypted with the passphrase if one is provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - API key.
+    /// * `secret_pem` - API secret, a private key in PEM format.
+    /// * `passphrase` - Passphrase the private key is encrypted with, if any.
+    pub fn with_authentication_pem(
+        self,
+        key: &str,
+        secret_pem: &str,
+        passphrase: Option<&str>,
+    ) -> Self {
+        let mut builder = self.with_authentication(key, secret_pem);
+        builder.api_passphrase = passphrase.map(str::to_string);
+        builder
+    }
+
     /// Enables or disables the public connection.
     ///
     /// # Arguments
@@ -157,6 +199,83 @@ impl WebSocketClientBuilder {
         self
     }
 
+    /// Sets the clock used as the source of the current time, defaults to the system clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Clock that implements the `Clock` trait.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets whether the JSON Web Token used to authenticate subscriptions is cached. When
+    /// enabled, a token is reused until it is near expiry instead of being signed for every
+    /// subscription message. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Enable or disable caching of tokens.
+    pub fn cache_jwt(mut self, enable: bool) -> Self {
+        self.cache_jwt = enable;
+        self
+    }
+
+    /// Sets a hook that is called after a successful reconnect with the window of time the
+    /// WebSocket was disconnected for. Messages sent during this window are lost, the hook allows
+    /// for them to be backfilled using the REST API.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Asynchronous function called with the disconnected window.
+    pub fn on_reconnect<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(ReconnectWindow) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook = move |window: ReconnectWindow| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+            Box::pin(hook(window))
+        };
+        self.on_reconnect = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a hook that is called whenever a message waits on the rate limits before being sent,
+    /// with the amount of time it waited for.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Function called with the amount of time a message waited.
+    pub fn on_throttle<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.on_throttle = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the maximum size of an incoming message in bytes, messages that are larger cause the
+    /// connection to error. Large level2 snapshots may exceed the default of 64 MiB.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Maximum size of a message in bytes.
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.socket_config.max_message_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum size of a single incoming frame in bytes, frames that are larger cause
+    /// the connection to error. Defaults to 16 MiB.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Maximum size of a frame in bytes.
+    pub fn max_frame_size(mut self, size: usize) -> Self {
+        self.socket_config.max_frame_size = Some(size);
+        self
+    }
+
     /// Builds the `WebSocketClient`.
     ///
     /// # Errors
@@ -178,26 +297,89 @@ impl WebSocketClientBuilder {
             let secret = self.api_secret.ok_or_else(|| {
                 CbError::BadPrivateKey("API secret is required for authentication.".to_string())
             })?;
-            Some(Jwt::new(&key, &secret)?)
+            let jwt = Jwt::new(
+                &key,
+                &secret,
+                self.api_passphrase.as_deref(),
+                Arc::clone(&self.clock),
+            )?;
+            Some(if self.cache_jwt {
+                jwt.with_cache()
+            } else {
+                jwt
+            })
         } else {
             None
         };
 
         Ok(WebSocketClient {
             jwt,
-            public_bucket: self.public_bucket,
-            secure_bucket: self.secure_bucket,
+            public_bucket: Arc::new(Mutex::new(TokenBucket::new(
+                RateLimits::max_tokens(false, true),
+                RateLimits::refresh_rate(false, true),
+                self.on_throttle.clone(),
+            ))),
+            secure_bucket: Arc::new(Mutex::new(TokenBucket::new(
+                RateLimits::max_tokens(false, false),
+                RateLimits::refresh_rate(false, false),
+                self.on_throttle,
+            ))),
             public_tx: Arc::new(Mutex::new(None)),
             secure_tx: Arc::new(Mutex::new(None)),
             enable_public: self.enable_public,
             enable_user: self.enable_user,
             max_retries: self.max_retries,
             subscriptions: Arc::new(Mutex::new(WebSocketSubscriptions::new())),
+            clock: self.clock,
+            on_reconnect: self.on_reconnect,
+            socket_config: self.socket_config,
+            public_url: self.public_url,
+            secure_url: self.secure_url,
         })
     }
+
+    /// Builds a `WebSocketPool` that shards product subscriptions across several connections.
+    /// Each client in the pool has its own connections and subscriptions, sharing the rate
+    /// limits and credentials of the builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `connections` - Amount of clients (connections per endpoint) in the pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if `connections` is zero, if the API key or secret are missing, or if
+    /// both public and secure connections are disabled.
+    pub fn build_pool(self, connections: usize) -> CbResult<WebSocketPool> {
+        if connections == 0 {
+            return Err(CbError::BadConnection(
+                "A pool requires at least one connection.".to_string(),
+            ));
+        }
+
+        let client = self.build()?;
+        let clients = (0..connections)
+            .map(|_| WebSocketClient {
+                public_tx: Arc::new(Mutex::new(None)),
+                secure_tx: Arc::new(Mutex::new(None)),
+                subscriptions: Arc::new(Mutex::new(WebSocketSubscriptions::new())),
+                ..client.clone()
+            })
+            .collect();
+
+        Ok(WebSocketPool::new(clients))
+    }
 }
 
 /// A WebSocket Client used to interactive with the Coinbase Advanced API. Provides easy-access to subscribing and listening to the WebSocket.
+///
+/// The client is `Send + Sync` and cheap to `Clone`. Clones share the same connections,
+/// subscriptions, and rate limiting token buckets.
+///
+/// NOTE: The Advanced Trade WebSocket does not provide a cancel-on-disconnect (cancel-after)
+/// message, open orders remain on the book if the connection is lost. Use the `on_reconnect` hook
+/// of the builder with `OrderApi::cancel_all_orders` to cancel or reconcile orders once the
+/// connection is reestablished.
 pub struct WebSocketClient {
     /// Signs the messages sent.
     pub(crate) jwt: Option<Jwt>,
@@ -217,6 +399,16 @@ pub struct WebSocketClient {
     pub(crate) max_retries: u32,
     /// Tracked subscriptions.
     pub(crate) subscriptions: Arc<Mutex<WebSocketSubscriptions>>,
+    /// Source of the current time.
+    pub(crate) clock: Arc<dyn Clock>,
+    /// Called after a successful reconnect.
+    pub(crate) on_reconnect: Option<Arc<ReconnectCallback>>,
+    /// Configuration for the underlying WebSocket connections.
+    pub(crate) socket_config: WebSocketConfig,
+    /// Address of the public endpoint.
+    pub(crate) public_url: String,
+    /// Address of the secure user endpoint.
+    pub(crate) secure_url: String,
 }
 
 impl Clone for WebSocketClient {
@@ -231,6 +423,11 @@ impl Clone for WebSocketClient {
             enable_user: self.enable_user,
             max_retries: self.max_retries,
             subscriptions: self.subscriptions.clone(),
+            clock: self.clock.clone(),
+            on_reconnect: self.on_reconnect.clone(),
+            socket_config: self.socket_config,
+            public_url: self.public_url.clone(),
+            secure_url: self.secure_url.clone(),
         }
     }
 }
@@ -261,10 +458,14 @@ impl WebSocketClient {
     async fn connect_endpoint(&mut self, endpoint_type: &EndpointType) -> CbResult<Endpoint> {
         match endpoint_type {
             EndpointType::Public => {
-                let (public_socket, _) = connect_async(PUBLIC_ENDPOINT).await.map_err(|why| {
-                    CbError::BadConnection(format!(
-                        "Unable to establish public WebSocket connection: {why}",
-                    ))
+                let (public_socket, _) = connect_async_with_config(
+                    self.public_url.as_str(),
+                    Some(self.socket_config),
+                    false,
+                )
+                .await
+                .map_err(|why| {
+                    connection_error("Unable to establish public WebSocket connection", &why)
                 })?;
                 let (public_sink, stream) = public_socket.split();
                 {
@@ -274,10 +475,14 @@ impl WebSocketClient {
                 Ok(Endpoint::Public((EndpointType::Public, stream)))
             }
             EndpointType::User => {
-                let (secure_socket, _) = connect_async(SECURE_ENDPOINT).await.map_err(|why| {
-                    CbError::BadConnection(format!(
-                        "Unable to establish secure user WebSocket connection: {why}",
-                    ))
+                let (secure_socket, _) = connect_async_with_config(
+                    self.secure_url.as_str(),
+                    Some(self.socket_config),
+                    false,
+                )
+                .await
+                .map_err(|why| {
+                    connection_error("Unable to establish secure user WebSocket connection", &why)
                 })?;
                 let (secure_sink, stream) = secure_socket.split();
                 {
@@ -289,6 +494,15 @@ impl WebSocketClient {
         }
     }
 
+    /// Closes the connections to the endpoints, sending a close frame to the service.
+    pub(crate) async fn disconnect(&self) {
+        for tx in [&self.public_tx, &self.secure_tx] {
+            if let Some(mut sink) = tx.lock().await.take() {
+                let _ = sink.close().await;
+            }
+        }
+    }
+
     /// Reconnects to a specific endpoint. Returns the reader of the endpoint.
     ///
     /// # Errors
@@ -316,6 +530,7 @@ impl WebSocketClient {
     /// # Errors
     ///
     /// Returns a `CbError` if the WebSocket connection fails or auto-reconnect is disabled.
+    /// Rejected credentials are returned immediately since retrying will not help.
     async fn wait_on_reconnect(&mut self, endpoint_type: &EndpointType) -> CbResult<Endpoint> {
         if self.max_retries == 0 {
             return Err(CbError::BadConnection(
@@ -330,6 +545,7 @@ impl WebSocketClient {
         while retries < self.max_retries {
             match self.reconnect(endpoint_type).await {
                 Ok(endpoint) => return Ok(endpoint),
+                Err(why @ CbError::Unauthorized(_)) => return Err(why),
                 Err(why) => {
                     eprintln!(
                         "Failed to reconnect WebSocket: {why}. Retrying in {retry_delay} seconds..."
@@ -347,11 +563,11 @@ impl WebSocketClient {
     }
 
     /// Handles reconnection logic for endpoints.
-    async fn handle_reconnection(&mut self, stream: EndpointStream) -> Option<EndpointStream> {
+    async fn handle_reconnection(&mut self, stream: EndpointStream) -> CbResult<EndpointStream> {
         match stream {
             EndpointStream::Single(route, _) => {
                 // Reconnect and return a new Single EndpointStream.
-                self.wait_on_reconnect(&route).await.ok().map(Into::into)
+                self.wait_on_reconnect(&route).await.map(Into::into)
             }
             EndpointStream::Multiple(_) => {
                 // Obtain all the endpoints that need to be reconnected.
@@ -363,11 +579,12 @@ impl WebSocketClient {
                 // Iterate over each endpoint and attempt to reconnect.
                 let mut new_endpoints = WebSocketEndpoints::default();
                 for endpoint_type in keys {
-                    if let Ok(new_endpoint) = self.wait_on_reconnect(&endpoint_type).await {
-                        new_endpoints.add(endpoint_type.clone(), new_endpoint);
-                    } else {
-                        eprintln!("Failed to reconnect: {endpoint_type:?}");
-                        return None;
+                    match self.wait_on_reconnect(&endpoint_type).await {
+                        Ok(new_endpoint) => new_endpoints.add(endpoint_type.clone(), new_endpoint),
+                        Err(why) => {
+                            eprintln!("Failed to reconnect: {endpoint_type:?}");
+                            return Err(why);
+                        }
                     }
                 }
 
@@ -386,13 +603,25 @@ impl WebSocketClient {
                     select_all.push(stream);
                 }
 
-                Some(EndpointStream::Multiple(select_all))
+                Ok(EndpointStream::Multiple(select_all))
             }
+            // User-provided sources cannot be reconnected.
+            EndpointStream::Source(_) => Err(CbError::BadConnection(
+                "User-provided sources cannot be reconnected.".to_string(),
+            )),
         }
     }
 
     /// Listens to WebSocket readers, supporting both single and multiple endpoints.
     ///
+    /// Parsing errors are delivered to the callback and listening continues. When the connection
+    /// is lost, a message of the `MessageKind::Connection` kind with a disconnected status is
+    /// delivered, followed by a reconnected status once the connection is reestablished. If the
+    /// reconnect fails or is disabled, the `CbError::BadConnection` error is delivered and
+    /// listening stops. If the service rejects the credentials while reconnecting with a 401 or
+    /// 403 response, such as for an expired key, the `CbError::Unauthorized` error is delivered
+    /// and listening stops without retrying.
+    ///
     /// # Arguments
     ///
     /// * `endpoints` - A single `Endpoint` or multiple `WebSocketEndpoints`.
@@ -407,21 +636,52 @@ impl WebSocketClient {
         loop {
             while let Some(message) = stream.next().await {
                 if let Some(result) = Self::process_message(message) {
-                    if let Err(CbError::BadConnection(_)) = &result {
+                    if let Err(CbError::BadConnection(reason)) = &result {
+                        // Notify the callback of the disconnect.
+                        let disconnected_at = self.clock.now();
+                        callback
+                            .message_callback(Ok(Message::disconnected(reason, disconnected_at)))
+                            .await;
+
                         // Handle reconnection logic.
-                        if let Some(new_stream) = self.handle_reconnection(stream).await {
+                        let reconnected = self.handle_reconnection(stream).await;
+                        if let Ok(new_stream) = reconnected {
+                            let window = ReconnectWindow {
+                                disconnected_at,
+                                reconnected_at: self.clock.now(),
+                            };
+
+                            // Notify of the disconnected window to allow for backfilling.
+                            if let Some(hook) = &self.on_reconnect {
+                                hook(window).await;
+                            }
+                            callback
+                                .message_callback(Ok(Message::reconnected(window)))
+                                .await;
+
                             // Restart the loop with the new streams.
                             stream = new_stream;
                             break;
                         }
 
-                        // Reconnection failed, exit.
+                        // Reconnection failed, deliver the error and exit.
+                        match reconnected {
+                            Err(why @ CbError::Unauthorized(_)) => {
+                                callback.message_callback(Err(why)).await;
+                            }
+                            _ => callback.message_callback(result).await,
+                        }
                         return;
                     }
 
                     callback.message_callback(result).await;
                 }
             }
+
+            // User-provided sources end once exhausted.
+            if let EndpointStream::Source(_) = stream {
+                return;
+            }
         }
     }
 
@@ -491,7 +751,7 @@ impl WebSocketClient {
                 r#type: action.to_string(),
                 product_ids: product_ids.to_vec(),
                 channel: channel.clone(),
-                timestamp: time::now().to_string(),
+                timestamp: self.clock.now().to_string(),
             })
         } else {
             Subscription::Secure(SecureSubscription {
@@ -591,6 +851,45 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Subscribes to the Channel provided for every active product that can currently be traded.
+    /// The product catalog is obtained with the Product API and the subscriptions are sent in
+    /// chunks to keep the size of each message reasonable. Requires the `trade` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The Channel that is being subscribed to.
+    /// * `product_api` - Product API used to obtain the product catalog.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if the products could not be obtained or if the public or secure user
+    /// connection is not enabled.
+    #[cfg(feature = "trade")]
+    pub async fn subscribe_all_products(
+        &mut self,
+        channel: &Channel,
+        product_api: &mut ProductApi,
+    ) -> CbResult<Vec<String>> {
+        let products = product_api.get_bulk(&ProductListQuery::default()).await?;
+        let product_ids: Vec<String> = products
+            .into_iter()
+            .filter(|product| {
+                product.status.eq_ignore_ascii_case("online")
+                    && !product.is_disabled
+                    && !product.trading_disabled
+                    && !product.cancel_only
+                    && !product.view_only
+            })
+            .map(|product| product.product_id)
+            .collect();
+
+        for chunk in product_ids.chunks(SUBSCRIBE_CHUNK_SIZE) {
+            self.subscribe(channel, chunk).await?;
+        }
+
+        Ok(product_ids)
+    }
+
     /// Unsubscribes from the product IDs for the Channel provided. This will stop additional updates
     /// coming in via the `listener` for these products.
     ///
@@ -630,6 +929,79 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Converges the tracked subscriptions to the desired subscriptions, only sending the
+    /// subscribe and unsubscribe messages required. Channels that are not desired are
+    /// unsubscribed from entirely. A channel with no product IDs, such as heartbeats, is
+    /// subscribed to without any products.
+    ///
+    /// # Arguments
+    ///
+    /// * `desired` - Channels and the product IDs that should be subscribed to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if any of the subscriptions or unsubscriptions fail.
+    pub async fn reconcile_subscriptions(
+        &mut self,
+        desired: &[(Channel, Vec<String>)],
+    ) -> CbResult<()> {
+        let mut wanted: HashMap<Channel, HashSet<String>> = HashMap::new();
+        for (channel, product_ids) in desired {
+            wanted
+                .entry(channel.clone())
+                .or_default()
+                .extend(product_ids.iter().cloned());
+        }
+
+        let mut tracked: HashMap<Channel, HashSet<String>> = HashMap::new();
+        {
+            let subs = self.subscriptions.lock().await;
+            for endpoint in subs.get_keys() {
+                for (channel, product_ids) in subs.get(&endpoint).await {
+                    tracked.entry(channel).or_default().extend(product_ids);
+                }
+            }
+        }
+
+        // Unsubscribe first to stay within the limits of the connection.
+        for (channel, current) in &tracked {
+            let stale = match wanted.get(channel) {
+                None if current.is_empty() => {
+                    self.unsubscribe(channel, &[]).await?;
+                    continue;
+                }
+                None => sorted(current.iter()),
+                Some(product_ids) => sorted(current.difference(product_ids)),
+            };
+
+            if !stale.is_empty() {
+                self.unsubscribe(channel, &stale).await?;
+            }
+        }
+
+        for (channel, product_ids) in &wanted {
+            let missing = match tracked.get(channel) {
+                // Tracked products were unsubscribed from above, leaving the channel untracked.
+                Some(current) if product_ids.is_empty() && !current.is_empty() => {
+                    self.subscribe(channel, &[]).await?;
+                    continue;
+                }
+                None if product_ids.is_empty() => {
+                    self.subscribe(channel, &[]).await?;
+                    continue;
+                }
+                None => sorted(product_ids.iter()),
+                Some(current) => sorted(product_ids.difference(current)),
+            };
+
+            if !missing.is_empty() {
+                self.subscribe(channel, &missing).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Watches candles for a set of products, producing candles once they are considered complete.
     ///
     /// # Argument
@@ -671,3 +1043,114 @@ impl WebSocketClient {
         }
     }
 }
+
+/// Connects, subscribes, and yields the messages of the subscriptions for as long as the stream
+/// is held. Disconnections are handled by reconnecting and resubscribing, reconnecting is enabled
+/// if the builder does not already allow it. Reconnects are reported with messages on the
+/// `MessageKind::Connection` kind, messages missed while disconnected can be backfilled using the
+/// `on_reconnect` hook of the builder. Dropping the stream closes the connections.
+///
+/// The stream yields a single error and ends if the initial connection or subscriptions fail, any
+/// connections that were established are closed.
+///
+/// # Arguments
+///
+/// * `builder` - Builder used to create the client, such as with authentication.
+/// * `subs` - Channels and the product IDs to subscribe to.
+pub fn resilient_feed(
+    builder: WebSocketClientBuilder,
+    subs: Vec<(Channel, Vec<String>)>,
+) -> impl Stream<Item = CbResult<Message>> {
+    let builder = if builder.max_retries == 0 {
+        builder.auto_reconnect(true)
+    } else {
+        builder
+    };
+
+    stream::once(async move {
+        let mut pool = builder.build_pool(1)?;
+        let messages = pool.connect().await?;
+        for (channel, product_ids) in &subs {
+            if let Err(err) = pool.subscribe(channel, product_ids).await {
+                pool.disconnect().await;
+                return Err(err);
+            }
+        }
+        Ok((pool, messages))
+    })
+    .flat_map(|setup: CbResult<_>| match setup {
+        // The pool is the state of the stream, keeping the connections open until it is dropped.
+        Ok((pool, messages)) => Either::Left(stream::unfold(
+            (pool, messages),
+            |(pool, mut messages)| async move {
+                let message = messages.next().await?;
+                Some((message, (pool, messages)))
+            },
+        )),
+        Err(err) => Either::Right(stream::once(future::ready(Err(err)))),
+    })
+}
+
+/// Converts a failed connection attempt into an error, distinguishing rejected credentials from
+/// other connection failures.
+fn connection_error(context: &str, why: &WsError) -> CbError {
+    match why {
+        WsError::Http(response) if matches!(response.status().as_u16(), 401 | 403) => {
+            CbError::Unauthorized(format!("{context}: {}", response.status()))
+        }
+        _ => CbError::BadConnection(format!("{context}: {why}")),
+    }
+}
+
+/// Collects product IDs into a sorted vector, keeping the messages sent deterministic.
+fn sorted<'a>(product_ids: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut product_ids: Vec<String> = product_ids.cloned().collect();
+    product_ids.sort();
+    product_ids
+}
+
+/// Compile-time assertion that the client can be shared across threads.
+fn _assert_send_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<WebSocketClient>();
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    use super::{resilient_feed, WebSocketClientBuilder};
+    use crate::models::websocket::Channel;
+
+    #[tokio::test]
+    async fn resilient_feed_closes_connection_when_subscribe_fails() {
+        // Local service that records the messages received until the connection ends.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let service = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let mut received = Vec::new();
+            while let Some(Ok(message)) = socket.next().await {
+                received.push(message);
+            }
+            received
+        });
+
+        // The user channel fails to subscribe after connecting since it is not enabled.
+        let mut builder = WebSocketClientBuilder::new();
+        builder.public_url = format!("ws://{address}");
+        let subs = vec![(Channel::Heartbeats, vec![]), (Channel::User, vec![])];
+
+        let results: Vec<_> = resilient_feed(builder, subs).collect().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+
+        let received = service.await.unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(matches!(received[0], WsMessage::Text(_)));
+        assert!(matches!(received[1], WsMessage::Close(_)));
+    }
+}
@@ -0,0 +1,345 @@
+//! # WebSocket Connection Pool
+//!
+//! `websocket_pool` shards product subscriptions across several `WebSocketClient`s. Coinbase
+//! limits the amount of products a single connection can receive updates for, the pool spreads
+//! the products across the connections and merges the messages into a single stream.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::errors::CbError;
+use crate::models::websocket::{Channel, EndpointStream, Message};
+use crate::traits::MessageCallback;
+use crate::types::CbResult;
+use crate::WebSocketClient;
+
+/// Forwards the messages of a single client in the pool to the merged stream.
+struct PoolForwarder {
+    /// Sends the messages to the merged stream.
+    tx: UnboundedSender<CbResult<Message>>,
+}
+
+#[async_trait]
+impl MessageCallback for PoolForwarder {
+    async fn message_callback(&mut self, msg: CbResult<Message>) {
+        // The receiver being dropped means the user is no longer interested in messages.
+        let _ = self.tx.unbounded_send(msg);
+    }
+}
+
+/// A pool of WebSocket Clients that shards product subscriptions across several connections.
+///
+/// Products are assigned to the clients in a round-robin fashion and remain with the same client
+/// while they are subscribed to on any channel. Each client reconnects and resubscribes independently using the
+/// settings of the builder, with the messages of every client delivered on a single stream.
+/// Created with `WebSocketClientBuilder::build_pool`.
+pub struct WebSocketPool {
+    /// Clients that make up the pool, each with their own connections.
+    clients: Vec<WebSocketClient>,
+    /// Index of the client each product is assigned to. [key: Product Id, value: Client Index]
+    assignments: HashMap<String, usize>,
+    /// Index of the client the next new product is assigned to.
+    next: usize,
+    /// Tasks listening to each of the clients.
+    listeners: Vec<JoinHandle<()>>,
+}
+
+impl WebSocketPool {
+    /// Creates a new pool from the clients provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `clients` - Clients that make up the pool, each with their own connections.
+    pub(crate) fn new(clients: Vec<WebSocketClient>) -> Self {
+        Self {
+            clients,
+            assignments: HashMap::new(),
+            next: 0,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Amount of clients in the pool.
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Checks if the pool has no clients.
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Clients that make up the pool.
+    pub fn clients(&self) -> &[WebSocketClient] {
+        &self.clients
+    }
+
+    /// Obtains the index of the client a product is assigned to, if it has been subscribed to.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_id` - Product ID to obtain the client for.
+    pub fn client_for(&self, product_id: &str) -> Option<usize> {
+        self.assignments.get(product_id).copied()
+    }
+
+    /// Connects every client in the pool and starts listening to them. Returns a single stream
+    /// that merges the messages of all clients, including the connection messages produced when a
+    /// client disconnects and reconnects. The stream ends once every client has stopped
+    /// listening. A subscription needs to be sent shortly after connecting or Coinbase will
+    /// close the connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if the pool is already connected or if any of the WebSocket
+    /// connections fail.
+    pub async fn connect(&mut self) -> CbResult<UnboundedReceiver<CbResult<Message>>> {
+        if !self.listeners.is_empty() {
+            return Err(CbError::BadConnection(
+                "WebSocket pool is already connected.".to_string(),
+            ));
+        }
+
+        // Connect all clients before listening so a failure leaves nothing running.
+        let mut streams = Vec::with_capacity(self.clients.len());
+        for index in 0..self.clients.len() {
+            let mut endpoints = match self.clients[index].connect().await {
+                Ok(mut endpoints) => endpoints.extract_to_vec(),
+                Err(err) => {
+                    // Close the connections of the clients that connected before the failure.
+                    self.disconnect().await;
+                    return Err(err);
+                }
+            };
+            let stream = if endpoints.len() == 1 {
+                EndpointStream::from(endpoints.remove(0))
+            } else {
+                EndpointStream::from(endpoints)
+            };
+            streams.push(stream);
+        }
+
+        let (tx, rx) = mpsc::unbounded();
+        for (client, stream) in self.clients.iter().zip(streams) {
+            let mut client = client.clone();
+            let forwarder = PoolForwarder { tx: tx.clone() };
+            self.listeners.push(tokio::spawn(async move {
+                client.listen(stream, forwarder).await;
+            }));
+        }
+
+        Ok(rx)
+    }
+
+    /// Stops listening to all clients in the pool, ending the merged stream.
+    pub fn shutdown(&mut self) {
+        for listener in self.listeners.drain(..) {
+            listener.abort();
+        }
+    }
+
+    /// Stops listening to all clients in the pool and closes their connections.
+    pub async fn disconnect(&mut self) {
+        self.shutdown();
+        for client in &self.clients {
+            client.disconnect().await;
+        }
+    }
+
+    /// Subscribes to the Channel provided, spreading the product IDs across the clients. Products
+    /// that are already subscribed to remain with the same client. Subscriptions without
+    /// product IDs, such as heartbeats, are sent to every client.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The Channel that is being subscribed to.
+    /// * `product_ids` - A vector of product IDs to listen for.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if any of the clients fail to subscribe.
+    pub async fn subscribe(&mut self, channel: &Channel, product_ids: &[String]) -> CbResult<()> {
+        if product_ids.is_empty() {
+            for client in &mut self.clients {
+                client.subscribe(channel, &[]).await?;
+            }
+            return Ok(());
+        }
+
+        for (index, products) in self.assign(product_ids) {
+            self.clients[index].subscribe(channel, &products).await?;
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes from the product IDs for the Channel provided on the clients they are
+    /// assigned to. Unsubscriptions without product IDs are sent to every client. Products that
+    /// are no longer subscribed to on any channel are unassigned, they are assigned to a client
+    /// again the next time they are subscribed to.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The Channel that is being changed to.
+    /// * `product_ids` - A vector of product IDs to no longer listen for.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CbError` if any of the clients fail to unsubscribe.
+    pub async fn unsubscribe(&mut self, channel: &Channel, product_ids: &[String]) -> CbResult<()> {
+        if product_ids.is_empty() {
+            for index in 0..self.clients.len() {
+                self.clients[index].unsubscribe(channel, &[]).await?;
+
+                let assigned: Vec<String> = self
+                    .assignments
+                    .iter()
+                    .filter(|(_, client)| **client == index)
+                    .map(|(product_id, _)| product_id.clone())
+                    .collect();
+                self.unassign_unsubscribed(index, &assigned).await;
+            }
+            return Ok(());
+        }
+
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for product_id in product_ids {
+            if let Some(index) = self.client_for(product_id) {
+                groups.entry(index).or_default().push(product_id.clone());
+            }
+        }
+
+        for (index, products) in groups {
+            self.clients[index].unsubscribe(channel, &products).await?;
+            self.unassign_unsubscribed(index, &products).await;
+        }
+        Ok(())
+    }
+
+    /// Unassigns the product IDs that are no longer subscribed to on any channel of the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the client the products are assigned to.
+    /// * `product_ids` - Product IDs to check.
+    async fn unassign_unsubscribed(&mut self, index: usize, product_ids: &[String]) {
+        let subscriptions = self.clients[index].subscriptions.lock().await;
+        for product_id in product_ids {
+            if !subscriptions.contains_product(product_id).await {
+                self.assignments.remove(product_id);
+            }
+        }
+    }
+
+    /// Groups the product IDs by the client they are assigned to, assigning new products to the
+    /// clients in a round-robin fashion.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_ids` - Product IDs to assign.
+    fn assign(&mut self, product_ids: &[String]) -> HashMap<usize, Vec<String>> {
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for product_id in product_ids {
+            let index = if let Some(index) = self.client_for(product_id) {
+                index
+            } else {
+                let index = self.next;
+                self.next = (self.next + 1) % self.clients.len();
+                self.assignments.insert(product_id.clone(), index);
+                index
+            };
+            groups.entry(index).or_default().push(product_id.clone());
+        }
+
+        groups
+    }
+}
+
+impl Drop for WebSocketPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    use crate::models::websocket::Channel;
+    use crate::{WebSocketClientBuilder, WebSocketPool};
+
+    /// Starts a local service that accepts the amount of connections provided, recording the
+    /// messages received on each until they end. Returns the address of the service.
+    async fn service(connections: usize) -> (String, JoinHandle<Vec<Vec<WsMessage>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            for _ in 0..connections {
+                let (socket, _) = listener.accept().await.unwrap();
+                sockets.push(tokio_tungstenite::accept_async(socket).await.unwrap());
+            }
+            // Refuse any additional connections.
+            drop(listener);
+
+            let mut received = Vec::new();
+            for mut socket in sockets {
+                let mut messages = Vec::new();
+                while let Some(Ok(message)) = socket.next().await {
+                    messages.push(message);
+                }
+                received.push(messages);
+            }
+            received
+        });
+        (address, handle)
+    }
+
+    /// Builds a pool of public clients connecting to the address provided.
+    fn pool(address: &str, connections: usize) -> WebSocketPool {
+        let mut pool = WebSocketClientBuilder::new()
+            .build_pool(connections)
+            .unwrap();
+        for client in &mut pool.clients {
+            client.public_url = address.to_string();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn connect_failure_closes_connected_clients() {
+        let (address, service) = service(1).await;
+        let mut pool = pool(&address, 2);
+
+        // The second client is refused, closing the first.
+        assert!(pool.connect().await.is_err());
+        assert!(pool.clients[0].public_tx.lock().await.is_none());
+
+        let received = service.await.unwrap();
+        assert!(matches!(received[0].last(), Some(WsMessage::Close(_))));
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_unassigns_products() {
+        let (address, _service) = service(2).await;
+        let mut pool = pool(&address, 2);
+        let _messages = pool.connect().await.unwrap();
+
+        let products = vec!["BTC-USD".to_string(), "ETH-USD".to_string()];
+        pool.subscribe(&Channel::Ticker, &products).await.unwrap();
+        pool.subscribe(&Channel::Level2, &products[..1]).await.unwrap();
+
+        // Still subscribed to on another channel, the assignment is kept.
+        pool.unsubscribe(&Channel::Ticker, &products).await.unwrap();
+        assert_eq!(pool.client_for("BTC-USD"), Some(0));
+        assert_eq!(pool.client_for("ETH-USD"), None);
+
+        pool.unsubscribe(&Channel::Level2, &[]).await.unwrap();
+        assert_eq!(pool.client_for("BTC-USD"), None);
+    }
+}